@@ -0,0 +1,163 @@
+//! the plan dates shared by the native and the tabula parser, on top of either chrono or time
+
+use std::fmt::{Display, Formatter};
+
+use thiserror::Error;
+
+#[cfg(not(any(feature = "chrono", feature = "time")))]
+compile_error!("hbs_date needs either the \"chrono\" or the \"time\" feature");
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum DateError {
+	#[error("{day}.{month}.{year} is not a valid date")]
+	Invalid { year: i32, month: u32, day: u32 },
+	#[error("\"{0}\" is not a date in the format dd.mm.yyyy")]
+	Malformed(String),
+}
+
+/// a validated calendar date, independent of the date library behind it
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Date {
+	year: i32,
+	month: u32,
+	day: u32,
+}
+
+impl Date {
+	pub fn from_ymd(year: i32, month: u32, day: u32) -> Result<Self, DateError> {
+		if backend::midnight_millis(year, month, day).is_none() {
+			return Err(DateError::Invalid { year, month, day });
+		}
+
+		Ok(Self { year, month, day })
+	}
+
+	/// parses the german "dd.mm.yyyy" format
+	pub fn parse_dmy(s: &str) -> Result<Self, DateError> {
+		let malformed = || DateError::Malformed(s.to_owned());
+
+		let mut parts = s.trim().split('.');
+
+		let day = parts.next().and_then(|p| p.trim().parse().ok()).ok_or_else(malformed)?;
+		let month = parts.next().and_then(|p| p.trim().parse().ok()).ok_or_else(malformed)?;
+		let year = parts.next().and_then(|p| p.trim().parse().ok()).ok_or_else(malformed)?;
+
+		if parts.next().is_some() {
+			return Err(malformed());
+		}
+
+		Self::from_ymd(year, month, day)
+	}
+
+	pub fn year(&self) -> i32 {
+		self.year
+	}
+
+	pub fn month(&self) -> u32 {
+		self.month
+	}
+
+	pub fn day(&self) -> u32 {
+		self.day
+	}
+
+	/// the utc day the timestamp falls on, like `pdf_issue_date` of a schedule
+	pub fn from_timestamp_millis(millis: i64) -> Option<Self> {
+		let (year, month, day) = backend::ymd_of_epoch_day(millis.div_euclid(86_400_000))?;
+
+		Some(Self { year, month, day })
+	}
+
+	/// the day of the week, 1 is monday and 7 sunday
+	pub fn weekday(&self) -> u32 {
+		// the epoch was a thursday
+		((self.timestamp_millis().div_euclid(86_400_000) + 3).rem_euclid(7) + 1) as u32
+	}
+
+	/// milliseconds since the unix epoch at midnight utc of this day
+	pub fn timestamp_millis(&self) -> i64 {
+		backend::midnight_millis(self.year, self.month, self.day).expect("date was validated on construction")
+	}
+}
+
+impl Display for Date {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{:02}.{:02}.{:04}", self.day, self.month, self.year)
+	}
+}
+
+#[cfg(feature = "chrono")]
+impl From<Date> for chrono::NaiveDate {
+	fn from(date: Date) -> Self {
+		chrono::NaiveDate::from_ymd_opt(date.year, date.month, date.day).expect("date was validated on construction")
+	}
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDate> for Date {
+	fn from(date: chrono::NaiveDate) -> Self {
+		use chrono::Datelike;
+
+		Self {
+			year: date.year(),
+			month: date.month(),
+			day: date.day(),
+		}
+	}
+}
+
+#[cfg(feature = "chrono")]
+mod backend {
+	pub(super) fn midnight_millis(year: i32, month: u32, day: u32) -> Option<i64> {
+		let epoch = chrono::NaiveDate::from_ymd_opt(1970, 1, 1)?;
+
+		Some(chrono::NaiveDate::from_ymd_opt(year, month, day)?.signed_duration_since(epoch).num_milliseconds())
+	}
+
+	pub(super) fn ymd_of_epoch_day(days: i64) -> Option<(i32, u32, u32)> {
+		use chrono::Datelike;
+
+		// 1970-01-01 is day 719163 counting from 0001-01-01
+		let date = chrono::NaiveDate::from_num_days_from_ce_opt(i32::try_from(days + 719_163).ok()?)?;
+
+		Some((date.year(), date.month(), date.day()))
+	}
+}
+
+#[cfg(all(feature = "time", not(feature = "chrono")))]
+mod backend {
+	pub(super) fn midnight_millis(year: i32, month: u32, day: u32) -> Option<i64> {
+		let month = time::Month::try_from(u8::try_from(month).ok()?).ok()?;
+		let date = time::Date::from_calendar_date(year, month, u8::try_from(day).ok()?).ok()?;
+
+		Some(date.midnight().assume_utc().unix_timestamp() * 1000)
+	}
+
+	pub(super) fn ymd_of_epoch_day(days: i64) -> Option<(i32, u32, u32)> {
+		// 1970-01-01 is julian day 2440588
+		let date = time::Date::from_julian_day(i32::try_from(days + 2_440_588).ok()?).ok()?;
+
+		Some((date.year(), u8::from(date.month()) as u32, date.day() as u32))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn dates_round_trip_through_timestamps() {
+		let date = Date::parse_dmy("01.02.2021").unwrap();
+
+		assert_eq!(date.timestamp_millis(), 1_612_137_600_000);
+		assert_eq!(Date::from_timestamp_millis(date.timestamp_millis() + 1), Some(date));
+		assert_eq!(date.weekday(), 1);
+	}
+
+	#[test]
+	fn impossible_dates_are_rejected() {
+		assert!(matches!(Date::parse_dmy("30.02.2021"), Err(DateError::Invalid { year: 2021, month: 2, day: 30 })));
+		assert!(matches!(Date::parse_dmy("2021-02-01"), Err(DateError::Malformed(_))));
+	}
+}
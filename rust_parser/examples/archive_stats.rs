@@ -0,0 +1,75 @@
+//! extracts a directory of archived plans and prints how many substitutions every class had
+//!
+//! usage: cargo run --example archive_stats -- <pdf-dir> [<parallelism>]
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use hbs_table_extractor::extract_directory;
+use hbs_table_extractor::substitution_common::SubstitutionColumn;
+
+fn main() -> ExitCode {
+	let args = std::env::args().skip(1).collect::<Vec<String>>();
+
+	let (dir, parallelism) = match args.as_slice() {
+		[dir] => (PathBuf::from(dir), 4),
+		[dir, parallelism] => match parallelism.parse() {
+			Ok(parallelism) => (PathBuf::from(dir), parallelism),
+			Err(_) => {
+				eprintln!("parallelism must be a number");
+				return ExitCode::from(2);
+			}
+		},
+		_ => {
+			eprintln!("usage: archive_stats <pdf-dir> [<parallelism>]");
+			return ExitCode::from(2);
+		}
+	};
+
+	let report = match extract_directory(&dir, parallelism, |p| eprint!("\r{}/{}", p.finished, p.total)) {
+		Ok(report) => report,
+		Err(e) => {
+			eprintln!("can't read {}: {e}", dir.display());
+			return ExitCode::FAILURE;
+		}
+	};
+
+	eprintln!();
+
+	let mut per_class: HashMap<&str, usize> = HashMap::new();
+
+	for (_, schedule) in &report.schedules {
+		for (class, column) in &schedule.entries {
+			*per_class.entry(class).or_default() += substitution_count(column);
+		}
+	}
+
+	let mut per_class = per_class.into_iter().collect::<Vec<(&str, usize)>>();
+	per_class.sort_by(|(c1, n1), (c2, n2)| n2.cmp(n1).then(c1.cmp(c2)));
+
+	println!("{} plans extracted, {} failed", report.schedules.len(), report.failures.len());
+	println!("{} substitutions in total", per_class.iter().map(|(_, n)| n).sum::<usize>());
+	println!();
+
+	for (class, count) in &per_class {
+		println!("{count:>6}  {class}");
+	}
+
+	if !report.failures.is_empty() {
+		println!();
+
+		for (path, error) in &report.failures {
+			println!("failed: {}: {error}", path.display());
+		}
+	}
+
+	if report.failures.is_empty() { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
+
+fn substitution_count(column: &SubstitutionColumn) -> usize {
+	[&column.block_0, &column.block_1, &column.block_2, &column.block_3, &column.block_4, &column.block_5]
+		.iter()
+		.filter(|b| b.is_some())
+		.count()
+}
@@ -0,0 +1,102 @@
+//! writes the substitutions of one class as an ics calendar, one all-day event per block with a substitution
+//!
+//! usage: cargo run --example class_ics -- <class> <out.ics> <pdf>...
+//!
+//! the event titles are in the language of `LANG`, German if it is not set or not supported.
+//!
+//! every pdf is a daily plan, the events are dated by the issue date of the plan.
+//! the block times differ between schools, so map the block number to a time here before using this for real.
+
+use std::error::Error;
+use std::fs::{write, File};
+use std::process::ExitCode;
+
+use hbs_table_extractor::{Date, HbsTableExtractor, Labels};
+use hbs_table_extractor::substitution_common::{Substitution, SubstitutionColumn, SubstitutionPDFExtractor};
+
+fn main() -> ExitCode {
+	let args = std::env::args().skip(1).collect::<Vec<String>>();
+
+	let (class, out, pdfs) = match args.as_slice() {
+		[class, out, pdfs @ ..] if !pdfs.is_empty() => (class, out, pdfs),
+		_ => {
+			eprintln!("usage: class_ics <class> <out.ics> <pdf>...");
+			return ExitCode::from(2);
+		}
+	};
+
+	match run(class, out, pdfs) {
+		Ok(events) => {
+			println!("{events} events for {class} written to {out}");
+			ExitCode::SUCCESS
+		}
+		Err(e) => {
+			eprintln!("{e}");
+			ExitCode::FAILURE
+		}
+	}
+}
+
+fn run(class: &str, out: &str, pdfs: &[String]) -> Result<usize, Box<dyn Error>> {
+	let mut calendar = String::from("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//hbs-table-extractor//class_ics//DE\r\n");
+	let mut events = 0;
+
+	let labels = std::env::var("LANG").ok()
+		.and_then(|lang| Labels::for_language(&lang))
+		.unwrap_or_default();
+
+	for pdf in pdfs {
+		let schedule = HbsTableExtractor::schedule_from_pdf(File::open(pdf)?)
+			.map_err(|e| format!("{pdf}: {e}"))?;
+
+		let date = Date::from_timestamp_millis(schedule.pdf_issue_date)
+			.ok_or_else(|| format!("{pdf}: issue date out of range"))?;
+
+		// a class without substitutions that day is not in the table at all
+		let column = match schedule.entries.get(class) {
+			Some(column) => column,
+			None => continue,
+		};
+
+		for (block, substitution) in blocks(column).into_iter().enumerate() {
+			if let Some(substitution) = substitution {
+				calendar.push_str(&event(class, date, block + 1, &labels, substitution));
+				events += 1;
+			}
+		}
+	}
+
+	calendar.push_str("END:VCALENDAR\r\n");
+	write(out, calendar)?;
+
+	Ok(events)
+}
+
+fn blocks(column: &SubstitutionColumn) -> [Option<&Substitution>; 6] {
+	[
+		column.block_0.as_ref(),
+		column.block_1.as_ref(),
+		column.block_2.as_ref(),
+		column.block_3.as_ref(),
+		column.block_4.as_ref(),
+		column.block_5.as_ref(),
+	]
+}
+
+fn event(class: &str, date: Date, block: usize, labels: &Labels, substitution: &Substitution) -> String {
+	let day = format!("{:04}{:02}{:02}", date.year(), date.month(), date.day());
+
+	format!(
+		"BEGIN:VEVENT\r\nUID:{day}-{block}-{}@hbs-table-extractor\r\nDTSTAMP:{day}T000000Z\r\nDTSTART;VALUE=DATE:{day}\r\nSUMMARY:{}\r\nEND:VEVENT\r\n",
+		escape(class),
+		escape(&format!("{}: {}", labels.block_number(block), substitution.0.join(" "))),
+	)
+}
+
+/// ics text values must not contain unescaped commas, semicolons or newlines
+fn escape(text: &str) -> String {
+	text.replace('\\', "\\\\")
+		.replace(';', "\\;")
+		.replace(',', "\\,")
+		.replace('\n', "\\n")
+}
@@ -0,0 +1,100 @@
+//! watches a folder of plans and publishes every new plan and the changes of every new revision to an mqtt broker
+//!
+//! usage: cargo run --example watch_mqtt --features mqtt -- <pdf-dir> <broker-host> [<port>]
+//!
+//! the modification time of a pdf is its revision, so replacing a plan with a newer export publishes the changes.
+//! plans are dropped, and their retained schedule cleared, at the end of their day.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::{read_dir, File};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::thread::sleep;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hbs_table_extractor::{HbsTableExtractor, MqttPublisher, MqttSettings, ScheduleStore};
+use hbs_table_extractor::substitution_common::{SubstitutionPDFExtractor, SubstitutionSchedule};
+
+/// how often the folder is scanned
+const INTERVAL: Duration = Duration::from_secs(30);
+/// plans expire at midnight at the end of their day
+const EXPIRE_AT: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn main() -> ExitCode {
+	let args = std::env::args().skip(1).collect::<Vec<String>>();
+
+	let (dir, host, port) = match args.as_slice() {
+		[dir, host] => (dir, host, 1883),
+		[dir, host, port] => match port.parse() {
+			Ok(port) => (dir, host, port),
+			Err(_) => {
+				eprintln!("port must be a number");
+				return ExitCode::from(2);
+			}
+		},
+		_ => {
+			eprintln!("usage: watch_mqtt <pdf-dir> <broker-host> [<port>]");
+			return ExitCode::from(2);
+		}
+	};
+
+	let mut settings = MqttSettings::default();
+	settings.host = host.clone();
+	settings.port = port;
+
+	let publisher = MqttPublisher::connect(settings);
+	let mut store = ScheduleStore::new(EXPIRE_AT);
+	let mut seen = HashMap::new();
+
+	loop {
+		if let Err(e) = scan(dir, &publisher, &mut store, &mut seen) {
+			eprintln!("{e}");
+		}
+
+		sleep(INTERVAL);
+	}
+}
+
+/// `seen` holds the revision of every pdf that was extracted already, so unchanged pdfs are skipped
+fn scan(dir: &str, publisher: &MqttPublisher, store: &mut ScheduleStore, seen: &mut HashMap<PathBuf, i64>) -> Result<(), Box<dyn Error>> {
+	let mut pdfs = Vec::new();
+
+	for entry in read_dir(dir)? {
+		let path = entry?.path();
+
+		if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf")) {
+			pdfs.push((millis(path.metadata()?.modified()?), path));
+		}
+	}
+
+	pdfs.sort();
+
+	for (revised, pdf) in pdfs {
+		if seen.insert(pdf.clone(), revised) == Some(revised) {
+			continue;
+		}
+
+		// a plan that can't be read must not stop the others
+		match extract(&pdf) {
+			Ok(schedule) => {
+				let events = store.ingest(revised, schedule)?;
+				publisher.publish_events(store, &events)?;
+			}
+			Err(e) => eprintln!("{}: {e}", pdf.display()),
+		}
+	}
+
+	let events = store.expire(millis(SystemTime::now()));
+	publisher.publish_events(store, &events)?;
+
+	Ok(())
+}
+
+fn extract(pdf: &Path) -> Result<SubstitutionSchedule, Box<dyn Error>> {
+	HbsTableExtractor::schedule_from_pdf(File::open(pdf)?)
+}
+
+fn millis(time: SystemTime) -> i64 {
+	time.duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or_default()
+}
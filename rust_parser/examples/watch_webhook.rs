@@ -0,0 +1,116 @@
+//! polls the url of the plan and posts the changed cells to a webhook as json whenever a new version appears
+//!
+//! usage: cargo run --example watch_webhook -- <plan-url> <webhook-url> [<interval-seconds>]
+//!
+//! only plain `http://` urls are supported to keep the example free of an http client dependency,
+//! swap `request` for a real client to talk to https endpoints.
+
+use std::error::Error;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::process::ExitCode;
+use std::thread::sleep;
+use std::time::Duration;
+
+use hbs_table_extractor::{diff_schedules, HbsTableExtractor};
+use hbs_table_extractor::substitution_common::{SubstitutionPDFExtractor, SubstitutionSchedule};
+use serde_json::json;
+
+fn main() -> ExitCode {
+	let args = std::env::args().skip(1).collect::<Vec<String>>();
+
+	let (plan_url, webhook_url, interval) = match args.as_slice() {
+		[plan_url, webhook_url] => (plan_url, webhook_url, 300),
+		[plan_url, webhook_url, interval] => match interval.parse() {
+			Ok(interval) => (plan_url, webhook_url, interval),
+			Err(_) => {
+				eprintln!("interval must be a number of seconds");
+				return ExitCode::from(2);
+			}
+		},
+		_ => {
+			eprintln!("usage: watch_webhook <plan-url> <webhook-url> [<interval-seconds>]");
+			return ExitCode::from(2);
+		}
+	};
+
+	let mut last: Option<(Vec<u8>, SubstitutionSchedule)> = None;
+
+	loop {
+		if let Err(e) = poll(plan_url, webhook_url, &mut last) {
+			eprintln!("{e}");
+		}
+
+		sleep(Duration::from_secs(interval));
+	}
+}
+
+fn poll(plan_url: &str, webhook_url: &str, last: &mut Option<(Vec<u8>, SubstitutionSchedule)>) -> Result<(), Box<dyn Error>> {
+	let pdf = request("GET", plan_url, None)?;
+
+	if last.as_ref().is_some_and(|(bytes, _)| *bytes == pdf) {
+		return Ok(());
+	}
+
+	let schedule = HbsTableExtractor::schedule_from_pdf(pdf.as_slice())?;
+
+	// the first version is only remembered, there is nothing to compare it with yet
+	if let Some((_, previous)) = last.as_ref() {
+		let changes = diff_schedules(previous, &schedule).into_iter()
+			.map(|d| json!({
+				"class": d.class,
+				"block": d.block.map(|b| b + 1),
+				"old": d.old,
+				"new": d.new,
+			}))
+			.collect::<Vec<_>>();
+
+		if !changes.is_empty() {
+			let body = json!({
+				"date": schedule.pdf_issue_date,
+				"changes": changes,
+			});
+
+			request("POST", webhook_url, Some(&body.to_string()))?;
+			println!("posted {} changes", body["changes"].as_array().map_or(0, Vec::len));
+		}
+	}
+
+	*last = Some((pdf, schedule));
+
+	Ok(())
+}
+
+/// a minimal http/1.0 request, returns the body of a 2xx response
+fn request(method: &str, url: &str, json: Option<&str>) -> Result<Vec<u8>, Box<dyn Error>> {
+	let rest = url.strip_prefix("http://").ok_or("only http:// urls are supported")?;
+	let (host, path) = rest.split_once('/').map_or((rest, "/".to_owned()), |(h, p)| (h, format!("/{p}")));
+	let address = if host.contains(':') { host.to_owned() } else { format!("{host}:80") };
+
+	let mut stream = TcpStream::connect(address)?;
+	let body = json.unwrap_or("");
+
+	write!(stream, "{method} {path} HTTP/1.0\r\nHost: {host}\r\nConnection: close\r\n")?;
+
+	if json.is_some() {
+		write!(stream, "Content-Type: application/json\r\nContent-Length: {}\r\n", body.len())?;
+	}
+
+	write!(stream, "\r\n{body}")?;
+
+	let mut response = Vec::new();
+	stream.read_to_end(&mut response)?;
+
+	let header_end = response.windows(4)
+		.position(|w| w == b"\r\n\r\n")
+		.ok_or("response without a header")?;
+
+	let status = String::from_utf8_lossy(&response[..header_end]);
+	let code = status.split_whitespace().nth(1).unwrap_or_default();
+
+	if !code.starts_with('2') {
+		return Err(format!("{method} {url}: status {code}").into());
+	}
+
+	Ok(response.split_off(header_end + 4))
+}
@@ -0,0 +1,138 @@
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+use crate::geometry::BoundingBox;
+use crate::HbsTableExtractor;
+
+/// layout of a document without any of its text, safe to share
+#[derive(Clone, Debug)]
+pub struct StructureReport {
+	pub pages: Vec<PageReport>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PageReport {
+	pub tables: Vec<TableReport>,
+	pub timing: PageTiming,
+}
+
+/// where the time for a page went
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct PageTiming {
+	/// decoding the content streams while loading
+	pub decode: Duration,
+	/// finding the tables on the page
+	pub detection: Duration,
+	/// splitting the tables into columns and cells
+	pub assembly: Duration,
+}
+
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct TableReport {
+	pub bbox: Option<BoundingBox>,
+	/// class headers found on the header row, the "Block" marker not included
+	pub header_count: usize,
+	pub columns: Vec<ColumnReport>,
+}
+
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ColumnReport {
+	/// spans from the first to the last horizontal line of the column, `None` if assembling failed
+	pub bbox: Option<BoundingBox>,
+	/// the rows below the header cell
+	pub rows: Vec<RowReport>,
+	/// why the column could not be assembled
+	pub error: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct RowReport {
+	pub bbox: BoundingBox,
+	/// number of text fragments inside the cell
+	pub text_count: usize,
+}
+
+impl HbsTableExtractor {
+	/// runs table detection and column assembly but only reports the structure, never the text
+	pub fn analyze(&self) -> Result<StructureReport, Box<dyn Error>> {
+		let mut pages = Vec::new();
+
+		for (page_idx, page) in self.pages.iter().enumerate() {
+			self.config.cancellation.check()?;
+
+			let mut tables = Vec::new();
+
+			let started = Instant::now();
+			let table_objects = page.extract_table_objects(&self.config)?;
+			let detection = started.elapsed();
+
+			let started = Instant::now();
+
+			for table in table_objects {
+				let mut columns = Vec::new();
+
+				for mut column in table.extract_columns() {
+					columns.push(match column.assemble() {
+						Ok(assembled) => {
+							let rows = assembled.rulings.windows(2)
+								.zip(&assembled.cells[1..])
+								.map(|(bounds, cell)| RowReport {
+									bbox: self.exposed(page_idx, BoundingBox {
+										left: assembled.left,
+										bottom: bounds[1],
+										right: assembled.right,
+										top: bounds[0],
+									}),
+									text_count: cell.len(),
+								})
+								.collect();
+
+							ColumnReport {
+								bbox: Some(self.exposed(page_idx, BoundingBox {
+									left: assembled.left,
+									bottom: *assembled.rulings.last().expect("assembled column without lines"),
+									right: assembled.right,
+									top: assembled.rulings[0],
+								})),
+								rows,
+								error: None,
+							}
+						}
+						Err(e) => ColumnReport {
+							bbox: None,
+							rows: Vec::new(),
+							error: Some(e.to_string()),
+						},
+					});
+				}
+
+				tables.push(TableReport {
+					bbox: table.bounding_box().map(|b| self.exposed(page_idx, b)),
+					header_count: columns.len(),
+					columns,
+				});
+			}
+
+			let timing = PageTiming {
+				decode: self.decode_times[page_idx],
+				detection,
+				assembly: started.elapsed(),
+			};
+
+			#[cfg(feature = "metrics")]
+			{
+				metrics::histogram!("hbs_extractor_page_decode_seconds", timing.decode);
+				metrics::histogram!("hbs_extractor_page_detection_seconds", timing.detection);
+				metrics::histogram!("hbs_extractor_page_assembly_seconds", timing.assembly);
+			}
+
+			pages.push(PageReport { tables, timing });
+		}
+
+		Ok(StructureReport { pages })
+	}
+}
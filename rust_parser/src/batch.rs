@@ -0,0 +1,138 @@
+use std::error::Error;
+use std::fs::{read_dir, OpenOptions};
+use std::io;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use substitution_common::SubstitutionSchedule;
+
+use crate::config::ExtractorConfig;
+use crate::recycle::Recycled;
+use crate::HbsTableExtractor;
+
+/// aggregated outcome of a batch run, both lists keep the order of the input paths
+pub struct BatchReport {
+	pub schedules: Vec<(PathBuf, SubstitutionSchedule)>,
+	pub failures: Vec<(PathBuf, String)>,
+}
+
+/// handed to the progress callback every time a pdf is done
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BatchProgress {
+	pub finished: usize,
+	pub total: usize,
+}
+
+/// extracts all pdfs with at most `parallelism` of them being processed at the same time
+pub fn extract_batch<P, F>(paths: &[P], parallelism: usize, on_progress: F) -> BatchReport
+	where P: AsRef<Path> + Sync, F: Fn(BatchProgress) + Sync
+{
+	extract_batch_with_config(paths, parallelism, &ExtractorConfig::default(), on_progress)
+}
+
+/// `extract_batch` with every pdf loaded with `config`
+pub(crate) fn extract_batch_with_config<P, F>(paths: &[P], parallelism: usize, config: &ExtractorConfig, on_progress: F) -> BatchReport
+	where P: AsRef<Path> + Sync, F: Fn(BatchProgress) + Sync
+{
+	let total = paths.len();
+	let next = AtomicUsize::new(0);
+	let finished = AtomicUsize::new(0);
+	let results = Mutex::new(Vec::with_capacity(total));
+
+	thread::scope(|scope| {
+		// the workers pull the next index themselves so only `parallelism` documents are ever in memory
+		for _ in 0..parallelism.clamp(1, total.max(1)) {
+			scope.spawn(|| {
+				// one set of buffers per worker, reused for every pdf it takes
+				let mut recycled = Recycled::default();
+
+				loop {
+					let idx = next.fetch_add(1, Ordering::Relaxed);
+
+					let path = match paths.get(idx) {
+						Some(path) => path.as_ref(),
+						None => break,
+					};
+
+					// a pdf that panics the extractor is a failure of its own, not of the whole batch
+					let result = match catch_unwind(AssertUnwindSafe(|| extract_file(path, config, &mut recycled))) {
+						Ok(result) => result.map_err(|e| e.to_string()),
+						Err(panic) => {
+							// the buffers might be half way through being filled
+							recycled = Recycled::default();
+							Err(format!("extractor panicked: {}", panic_message(panic.as_ref())))
+						}
+					};
+
+					results.lock().expect("batch worker panicked").push((idx, path.to_path_buf(), result));
+
+					on_progress(BatchProgress {
+						finished: finished.fetch_add(1, Ordering::Relaxed) + 1,
+						total,
+					});
+				}
+			});
+		}
+	});
+
+	let mut results = results.into_inner().expect("batch worker panicked");
+	results.sort_by_key(|(idx, _, _)| *idx);
+
+	let mut report = BatchReport {
+		schedules: Vec::new(),
+		failures: Vec::new(),
+	};
+
+	for (_, path, result) in results {
+		match result {
+			Ok(schedule) => report.schedules.push((path, schedule)),
+			Err(e) => report.failures.push((path, e)),
+		}
+	}
+
+	report
+}
+
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> &str {
+	match panic.downcast_ref::<&str>() {
+		Some(message) => message,
+		None => panic.downcast_ref::<String>().map_or("unknown cause", String::as_str),
+	}
+}
+
+/// runs `extract_batch` over every pdf directly inside `dir`, sorted by file name
+pub fn extract_directory<P, F>(dir: P, parallelism: usize, on_progress: F) -> io::Result<BatchReport>
+	where P: AsRef<Path>, F: Fn(BatchProgress) + Sync
+{
+	extract_directory_with_config(dir, parallelism, &ExtractorConfig::default(), on_progress)
+}
+
+/// `extract_directory` with every pdf loaded with `config`
+pub(crate) fn extract_directory_with_config<P, F>(dir: P, parallelism: usize, config: &ExtractorConfig, on_progress: F) -> io::Result<BatchReport>
+	where P: AsRef<Path>, F: Fn(BatchProgress) + Sync
+{
+	let mut paths = Vec::new();
+
+	for entry in read_dir(dir)? {
+		let path = entry?.path();
+
+		if path.is_file() && path.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf")) {
+			paths.push(path);
+		}
+	}
+
+	paths.sort();
+
+	Ok(extract_batch_with_config(&paths, parallelism, config, on_progress))
+}
+
+fn extract_file(path: &Path, config: &ExtractorConfig, recycled: &mut Recycled) -> Result<SubstitutionSchedule, Box<dyn Error>> {
+	let mut extractor = HbsTableExtractor::load_recycled(OpenOptions::new().read(true).open(path)?, config.clone(), recycled)?;
+	let schedule = extractor.schedule();
+	extractor.recycle(recycled);
+
+	schedule
+}
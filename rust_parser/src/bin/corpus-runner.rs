@@ -0,0 +1,158 @@
+//! runs the extractor over a directory of pdfs and compares the raw tables with the expected json next to them
+//!
+//! usage: corpus-runner <pdf-dir> [<expected-dir>] [--bless]
+//!
+//! the expected output of `plan.pdf` is `plan.json` in the expected directory, which defaults to the pdf directory.
+//! `--bless` writes the current output as the expected one instead of comparing.
+
+use std::error::Error;
+use std::fs::{read_dir, read_to_string, write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use hbs_table_extractor::{HbsTableExtractor, RawPage};
+
+enum Outcome {
+	Pass,
+	Fail(Vec<String>),
+	Missing,
+	Blessed,
+	Error(String),
+}
+
+fn main() -> ExitCode {
+	let mut args = std::env::args().skip(1).collect::<Vec<String>>();
+	let bless = args.iter().any(|a| a == "--bless");
+	args.retain(|a| a != "--bless");
+
+	let (pdf_dir, expected_dir) = match args.as_slice() {
+		[pdf_dir] => (PathBuf::from(pdf_dir), PathBuf::from(pdf_dir)),
+		[pdf_dir, expected_dir] => (PathBuf::from(pdf_dir), PathBuf::from(expected_dir)),
+		_ => {
+			eprintln!("usage: corpus-runner <pdf-dir> [<expected-dir>] [--bless]");
+			return ExitCode::from(2);
+		}
+	};
+
+	let pdfs = match list_pdfs(&pdf_dir) {
+		Ok(pdfs) => pdfs,
+		Err(e) => {
+			eprintln!("can't read {}: {e}", pdf_dir.display());
+			return ExitCode::from(2);
+		}
+	};
+
+	let (mut passed, mut failed) = (0, 0);
+
+	for pdf in pdfs {
+		let name = pdf.file_name().unwrap_or_default().to_string_lossy().into_owned();
+		let expected = expected_dir.join(pdf.with_extension("json").file_name().unwrap_or_default());
+
+		match run(&pdf, &expected, bless) {
+			Outcome::Pass => {
+				passed += 1;
+				println!("PASS    {name}");
+			}
+			Outcome::Blessed => {
+				passed += 1;
+				println!("BLESSED {name}");
+			}
+			Outcome::Missing => {
+				failed += 1;
+				println!("MISSING {name} (no {})", expected.display());
+			}
+			Outcome::Error(e) => {
+				failed += 1;
+				println!("ERROR   {name}: {e}");
+			}
+			Outcome::Fail(diffs) => {
+				failed += 1;
+				println!("FAIL    {name}");
+
+				for diff in diffs {
+					println!("        {diff}");
+				}
+			}
+		}
+	}
+
+	println!("{passed} passed, {failed} failed");
+
+	if failed == 0 { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
+
+fn list_pdfs(dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+	let mut pdfs = Vec::new();
+
+	for entry in read_dir(dir)? {
+		let path = entry?.path();
+
+		if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf")) {
+			pdfs.push(path);
+		}
+	}
+
+	pdfs.sort();
+	Ok(pdfs)
+}
+
+fn run(pdf: &Path, expected: &Path, bless: bool) -> Outcome {
+	let actual = match HbsTableExtractor::new(pdf).and_then(|mut e| e.extract_tables()) {
+		Ok(actual) => actual,
+		Err(e) => return Outcome::Error(e.to_string()),
+	};
+
+	if bless {
+		return match serde_json::to_string_pretty(&actual).map_err(Box::<dyn Error>::from).and_then(|j| Ok(write(expected, j)?)) {
+			Ok(()) => Outcome::Blessed,
+			Err(e) => Outcome::Error(e.to_string()),
+		};
+	}
+
+	if !expected.exists() {
+		return Outcome::Missing;
+	}
+
+	let expected = match read_to_string(expected).map_err(Box::<dyn Error>::from).and_then(|j| Ok(serde_json::from_str::<Vec<RawPage>>(&j)?)) {
+		Ok(expected) => expected,
+		Err(e) => return Outcome::Error(format!("can't read expected output: {e}")),
+	};
+
+	let diffs = diff(&expected, &actual);
+
+	if diffs.is_empty() { Outcome::Pass } else { Outcome::Fail(diffs) }
+}
+
+fn diff(expected: &[RawPage], actual: &[RawPage]) -> Vec<String> {
+	let mut diffs = Vec::new();
+
+	if expected.len() != actual.len() {
+		diffs.push(format!("expected {} pages, got {}", expected.len(), actual.len()));
+	}
+
+	for (p, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+		if expected.len() != actual.len() {
+			diffs.push(format!("page {p}: expected {} tables, got {}", expected.len(), actual.len()));
+		}
+
+		for (t, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+			if expected.len() != actual.len() {
+				diffs.push(format!("page {p}, table {t}: expected {} columns, got {}", expected.len(), actual.len()));
+			}
+
+			for (c, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+				if expected.len() != actual.len() {
+					diffs.push(format!("page {p}, table {t}, column {c}: expected {} cells, got {}", expected.len(), actual.len()));
+				}
+
+				for (r, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+					if expected != actual {
+						diffs.push(format!("page {p}, table {t}, column {c}, cell {r}: expected {expected:?}, got {actual:?}"));
+					}
+				}
+			}
+		}
+	}
+
+	diffs
+}
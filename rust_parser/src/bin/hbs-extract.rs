@@ -0,0 +1,779 @@
+//! command line front end of the extractor
+//!
+//! usage: hbs-extract <command> [<args>]
+//!
+//! commands:
+//!   dump-ops <pdf> [--page <n>] [--redact]   lists the content stream operations of a page (counting from 0, default 0),
+//!                                             `--redact` replaces all strings by their length
+//!   split <pdf> [<out-dir>]                   writes one pdf per plan date named <name>-<yyyy-mm-dd>.pdf,
+//!                                             into the directory of the pdf by default
+//!   extract <pdf> [--profile <toml>] [--bundle <out.hbsx>] [--json] [--tables] [--pivot block]
+//!                                             prints the schedule as long format csv, `--bundle` also writes
+//!                                             the result, diagnostics, profile and debug overlays to a bundle.
+//!                                             `--json` prints it as json instead, wrapped in a `JsonEnvelope`,
+//!                                             `--tables` the typed tables with their verbatim texts,
+//!                                             `--pivot block` one section per block with every class that has
+//!                                             something in it
+//!   preview <pdf> [--profile <toml>] [--port <n>]
+//!                                             extracts and serves the schedule next to the debug overlays of all pages
+//!                                             on localhost until interrupted, and opens it in the browser
+//!   profile init <pdf> [<out.toml>]           derives and calibrates a profile from a sample pdf,
+//!                                             printed if no file is given. it records the layout of the sample,
+//!                                             `extract` warns about plans deviating from it
+//!   profile diff-run <pdf> <old.toml> <new.toml>
+//!                                             lists the cells that change between the two profiles
+//!   self-test [--tabula]                      extracts a generated plan and compares the result with what was generated,
+//!                                             `--tabula` also runs the tabula backend on it
+//!   completions <bash|zsh|fish>               prints the shell completions
+//!   man                                       prints the man page, for packaging run `hbs-extract man > hbs-extract.1`
+//!
+//! `--profile` and `profile` need the `profile` feature, `--bundle` the `bundle` feature, `--tabula` the `tabula` feature.
+//! the arguments are parsed and the usage, completions and man page generated from `COMMANDS`, new commands and flags go there
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fs::{read, File};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::process::{Command, ExitCode};
+use std::time::Duration;
+
+use hbs_table_extractor::{dump_operations, escape, split_by_date, write_long_csv, write_signage_html, BlockEntry, Date, ExtractorConfig, HbsTableExtractor, JsonEnvelope, Labels, MarkerDate, SignageSettings, SyntheticPlan, Table};
+#[cfg(feature = "bundle")]
+use hbs_table_extractor::write_bundle;
+#[cfg(feature = "profile")]
+use hbs_table_extractor::{diff_schedules, ExtractionProfile};
+use hbs_table_extractor::substitution_common::SubstitutionSchedule;
+#[cfg(feature = "tabula")]
+use hbs_table_extractor::tabula_pdf_parser::TabulaParser;
+#[cfg(feature = "tabula")]
+use hbs_table_extractor::substitution_common::SubstitutionPDFExtractor;
+use lopdf::Document;
+
+/// how long `preview` waits for the request line of a connection
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// a command with its arguments, they are parsed and the usage, completions and man page are generated from these
+struct CommandSpec {
+	/// "profile init" for the commands of `profile`
+	name: &'static str,
+	/// the positional arguments as shown in the usage, optional ones in brackets
+	args: &'static str,
+	/// every flag with the placeholder of its value, if it takes one. a placeholder not in angle brackets is the only value
+	flags: &'static [(&'static str, Option<&'static str>)],
+	about: &'static str,
+	run: fn(&Args) -> Result<(), Box<dyn Error>>,
+}
+
+/// the arguments given to a command, checked against its `CommandSpec`
+struct Args<'a> {
+	positional: Vec<&'a str>,
+	flags: BTreeMap<&'static str, Option<&'a str>>,
+}
+
+impl<'a> Args<'a> {
+	fn flag(&self, flag: &str) -> bool {
+		self.flags.contains_key(flag)
+	}
+
+	fn value(&self, flag: &str) -> Option<&'a str> {
+		self.flags.get(flag).copied().flatten()
+	}
+}
+
+impl CommandSpec {
+	/// the command `args` start with and the arguments after its name
+	fn find(args: &[String]) -> Option<(&'static CommandSpec, &[String])> {
+		COMMANDS.iter().find_map(|command| {
+			let words = command.name.split(' ').count();
+			let name = args.get(..words)?;
+
+			name.iter().map(String::as_str).eq(command.name.split(' ')).then(|| (command, &args[words..]))
+		})
+	}
+
+	fn parse<'a>(&self, args: &'a [String]) -> Result<Args<'a>, Box<dyn Error>> {
+		let mut parsed = Args { positional: Vec::new(), flags: BTreeMap::new() };
+		let mut args = args.iter();
+
+		while let Some(arg) = args.next() {
+			if !arg.starts_with("--") {
+				parsed.positional.push(arg);
+				continue;
+			}
+
+			let (flag, placeholder) = self.flags.iter()
+				.find(|(flag, _)| flag == arg)
+				.ok_or_else(|| format!("{} has no flag {arg}\n{}", self.name, usage()))?;
+
+			let value = match placeholder {
+				Some(placeholder) => match args.next() {
+					Some(value) if placeholder.starts_with('<') || value == placeholder => Some(value.as_str()),
+					_ => return Err(format!("{flag} needs {placeholder}").into()),
+				},
+				None => None,
+			};
+
+			parsed.flags.insert(flag, value);
+		}
+
+		let required = self.args.split_whitespace().filter(|arg| !arg.starts_with('[')).count();
+
+		if !(required..=self.args.split_whitespace().count()).contains(&parsed.positional.len()) {
+			return Err(usage().into());
+		}
+
+		Ok(parsed)
+	}
+}
+
+const COMMANDS: &[CommandSpec] = &[
+	CommandSpec {
+		name: "dump-ops",
+		args: "<pdf>",
+		flags: &[("--page", Some("<n>")), ("--redact", None)],
+		about: "lists the content stream operations of a page, counting from 0. --redact replaces all strings by their length",
+		run: dump_ops,
+	},
+	CommandSpec {
+		name: "split",
+		args: "<pdf> [<out-dir>]",
+		flags: &[],
+		about: "writes one pdf per plan date named <name>-<yyyy-mm-dd>.pdf, into the directory of the pdf by default",
+		run: split,
+	},
+	CommandSpec {
+		name: "extract",
+		args: "<pdf>",
+		flags: &[("--profile", Some("<toml>")), ("--bundle", Some("<out.hbsx>")), ("--json", None), ("--tables", None), ("--pivot", Some("block"))],
+		about: "prints the schedule as long format csv. --bundle also writes the result, diagnostics, profile and debug \
+			overlays to a bundle, --json prints the schedule in a json envelope and --tables the typed tables with their \
+			verbatim texts instead. --pivot block lists every class with something in a block, one section per block, \
+			as json with --json",
+		run: extract,
+	},
+	CommandSpec {
+		name: "preview",
+		args: "<pdf>",
+		flags: &[("--profile", Some("<toml>")), ("--port", Some("<n>"))],
+		about: "serves the schedule next to the debug overlays of all pages on localhost until interrupted \
+			and opens it in the browser",
+		run: preview,
+	},
+	CommandSpec {
+		name: "profile init",
+		args: "<pdf> [<out.toml>]",
+		flags: &[],
+		about: "derives and calibrates a profile from a sample pdf, printed if no file is given. the profile records \
+			the layout of the sample and extract warns about plans deviating from it",
+		run: profile_init,
+	},
+	CommandSpec {
+		name: "profile diff-run",
+		args: "<pdf> <old.toml> <new.toml>",
+		flags: &[],
+		about: "lists the cells that change between extracting the pdf with the old and the new profile",
+		run: profile_diff_run,
+	},
+	CommandSpec {
+		name: "self-test",
+		args: "",
+		flags: &[("--tabula", None)],
+		about: "extracts a generated plan and compares the date, tables and classes with what was generated. \
+			--tabula also compares the classes tabula finds, which needs java and tabula.jar",
+		run: self_test,
+	},
+	CommandSpec {
+		name: "completions",
+		args: "<bash|zsh|fish>",
+		flags: &[],
+		about: "prints the shell completions",
+		run: completions,
+	},
+	CommandSpec {
+		name: "man",
+		args: "",
+		flags: &[],
+		about: "prints the man page as roff",
+		run: man,
+	},
+];
+
+fn usage() -> String {
+	let lines = COMMANDS.iter()
+		.map(|command| {
+			let flags = command.flags.iter()
+				.map(|(flag, value)| match value {
+					Some(value) => format!(" [{flag} {value}]"),
+					None => format!(" [{flag}]"),
+				})
+				.collect::<String>();
+
+			match command.args {
+				"" => format!("hbs-extract {}{flags}", command.name),
+				args => format!("hbs-extract {} {args}{flags}", command.name),
+			}
+		})
+		.collect::<Vec<String>>();
+
+	format!("usage: {}", lines.join("\n       "))
+}
+
+fn main() -> ExitCode {
+	let args = std::env::args().skip(1).collect::<Vec<String>>();
+
+	let result = match CommandSpec::find(&args) {
+		Some((command, rest)) => command.parse(rest).and_then(|args| (command.run)(&args)),
+		None => {
+			eprintln!("{}", usage());
+			return ExitCode::from(2);
+		}
+	};
+
+	match result {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(e) => {
+			eprintln!("{e}");
+			ExitCode::FAILURE
+		}
+	}
+}
+
+fn dump_ops(args: &Args) -> Result<(), Box<dyn Error>> {
+	let page = args.value("--page").map(str::parse).transpose()?.unwrap_or(0);
+
+	for line in dump_operations(File::open(args.positional[0])?, page, args.flag("--redact"))? {
+		println!("{line}");
+	}
+
+	Ok(())
+}
+
+fn split(args: &Args) -> Result<(), Box<dyn Error>> {
+	let pdf = Path::new(args.positional[0]);
+	let out_dir = match args.positional.get(1) {
+		Some(out_dir) => Path::new(out_dir),
+		None => pdf.parent().unwrap_or(Path::new(".")),
+	};
+
+	let stem = pdf.file_stem().ok_or("not a file")?.to_string_lossy();
+
+	for (date, mut day) in split_by_date(&Document::load(pdf)?, &MarkerDate::default())? {
+		let target = out_dir.join(format!("{}-{:04}-{:02}-{:02}.pdf", stem, date.year(), date.month(), date.day()));
+
+		day.save(&target)?;
+		println!("{}", target.display());
+	}
+
+	Ok(())
+}
+
+fn extract(args: &Args) -> Result<(), Box<dyn Error>> {
+	let pdf = args.positional[0];
+	let profile = args.value("--profile");
+	let bundle = args.value("--bundle");
+	let json = args.flag("--json");
+	let tables = args.flag("--tables");
+	let pivot = args.flag("--pivot");
+
+	let config = match profile {
+		Some(profile) => load_profile(profile)?,
+		None => ExtractorConfig::default(),
+	};
+
+	let source = read(pdf)?;
+	let mut extractor = HbsTableExtractor::load_from_with_config(source.as_slice(), config)?;
+
+	// a plan in a new template may still extract, but likely into the wrong cells
+	if let Ok(deviations) = extractor.template_drift() {
+		for deviation in deviations {
+			eprintln!("warning: the template changed since the profile was made: {deviation}");
+		}
+	}
+
+	// the typed tables keep the verbatim texts next to the post-processed ones
+	if tables {
+		if bundle.is_some() || pivot {
+			return Err("--tables can not be combined with --bundle or --pivot".into());
+		}
+
+		let tables = extractor.extract_typed_tables()?;
+		let profile = profile.map(read).transpose()?;
+
+		println!("{}", serde_json::to_string_pretty(&JsonEnvelope::new(&tables, &source, profile.as_deref()))?);
+
+		return Ok(());
+	}
+
+	if pivot {
+		if bundle.is_some() {
+			return Err("--pivot can not be combined with --bundle".into());
+		}
+
+		let tables = extractor.extract_typed_tables()?;
+		let mut blocks = BTreeMap::new();
+
+		for block in tables.iter().flat_map(Table::blocks).collect::<BTreeSet<u32>>() {
+			let entries = tables.iter().flat_map(|table| table.by_block(block)).collect::<Vec<BlockEntry>>();
+
+			if !entries.is_empty() {
+				blocks.insert(block, entries);
+			}
+		}
+
+		if json {
+			let profile = profile.map(read).transpose()?;
+			println!("{}", serde_json::to_string_pretty(&JsonEnvelope::new(&blocks, &source, profile.as_deref()))?);
+		} else {
+			print_blocks(&blocks);
+		}
+
+		return Ok(());
+	}
+
+	let schedule = match bundle {
+		Some(bundle) => extract_bundle(&mut extractor, profile, bundle)?,
+		None => extractor.schedule()?,
+	};
+
+	if json {
+		let profile = profile.map(read).transpose()?;
+		let envelope = JsonEnvelope::new(&schedule, &source, profile.as_deref());
+
+		println!("{}", serde_json::to_string_pretty(&envelope)?);
+	} else {
+		write_long_csv(&schedule, std::io::stdout().lock())?;
+	}
+
+	Ok(())
+}
+
+/// one section per block with a line per class
+fn print_blocks(blocks: &BTreeMap<u32, Vec<BlockEntry>>) {
+	for (idx, (block, entries)) in blocks.iter().enumerate() {
+		if idx > 0 {
+			println!();
+		}
+
+		println!("Block {block}");
+
+		for entry in entries {
+			match &entry.section {
+				Some(section) => println!("  {} ({section}): {}", entry.class, entry.lines.join(" / ")),
+				None => println!("  {}: {}", entry.class, entry.lines.join(" / ")),
+			}
+		}
+	}
+}
+
+fn preview(args: &Args) -> Result<(), Box<dyn Error>> {
+	let pdf = args.positional[0];
+	let port = args.value("--port").map(str::parse).transpose()?.unwrap_or(0);
+
+	let config = match args.value("--profile") {
+		Some(profile) => load_profile(profile)?,
+		None => ExtractorConfig::default(),
+	};
+	let mut extractor = HbsTableExtractor::load_from_with_config(File::open(pdf)?, config)?;
+
+	// a failing extraction is shown on the page, the overlays are what helps then
+	let schedule = match extractor.schedule() {
+		Ok(schedule) => {
+			let mut settings = SignageSettings::default();
+			settings.rows_per_page = usize::MAX;
+
+			let mut html = Vec::new();
+			write_signage_html(&schedule, &Labels::default(), &settings, &mut html)?;
+			html
+		}
+		Err(e) => format!("<!DOCTYPE html><html><body><pre>{}</pre></body></html>", escape(&e.to_string())).into_bytes(),
+	};
+
+	let mut index = format!(
+		"<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{}</title></head>\
+		<body style=\"margin:0;display:flex;height:100vh\">\
+		<iframe src=\"/schedule\" style=\"flex:1;border:0\"></iframe><div style=\"flex:1;overflow:auto\">",
+		escape(pdf),
+	);
+
+	for page in 0..extractor.page_count() {
+		index.push_str(&overlay(&extractor, page));
+	}
+
+	index.push_str("</div></body></html>");
+
+	let listener = TcpListener::bind(("127.0.0.1", port))?;
+	let url = format!("http://{}/", listener.local_addr()?);
+
+	println!("serving {pdf} at {url}, stop with ctrl-c");
+	open_browser(&url);
+
+	for stream in listener.incoming() {
+		if let Err(e) = serve(stream?, index.as_bytes(), &schedule) {
+			eprintln!("{e}");
+		}
+	}
+
+	Ok(())
+}
+
+/// answers a single request with the index or the schedule page
+fn serve(mut stream: TcpStream, index: &[u8], schedule: &[u8]) -> Result<(), Box<dyn Error>> {
+	// requests are answered one at a time, an idle preconnect of the browser must not block the others
+	stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+
+	let mut request = String::new();
+	BufReader::new(&stream).read_line(&mut request)?;
+
+	let (status, body) = match request.split_whitespace().nth(1) {
+		Some("/") => ("200 OK", index),
+		Some("/schedule") => ("200 OK", schedule),
+		_ => ("404 Not Found", &b"not found"[..]),
+	};
+
+	write!(stream, "HTTP/1.0 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len())?;
+	stream.write_all(body)?;
+
+	Ok(())
+}
+
+/// the debug overlay of a page, or an svg with the error in its place so one page can't hide the others
+fn overlay(extractor: &HbsTableExtractor, page: usize) -> String {
+	extractor.debug_svg(page).unwrap_or_else(|e| format!(
+		"<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 595 40\"><text x=\"4\" y=\"20\" fill=\"red\">page {page}: {}</text></svg>\n",
+		escape(&e.to_string()),
+	))
+}
+
+/// best effort, the url is printed anyway
+fn open_browser(url: &str) {
+	let opener = if cfg!(target_os = "macos") {
+		Command::new("open").arg(url).spawn()
+	} else if cfg!(windows) {
+		Command::new("cmd").args(["/C", "start", "", url]).spawn()
+	} else {
+		Command::new("xdg-open").arg(url).spawn()
+	};
+
+	if opener.is_err() {
+		eprintln!("open {url} in a browser");
+	}
+}
+
+#[cfg(feature = "profile")]
+fn load_profile(path: &str) -> Result<ExtractorConfig, Box<dyn Error>> {
+	ExtractionProfile::load(path)?.config()
+}
+
+#[cfg(not(feature = "profile"))]
+fn load_profile(_path: &str) -> Result<ExtractorConfig, Box<dyn Error>> {
+	Err("built without the profile feature".into())
+}
+
+/// extracts everything and writes it as a bundle together with the debug overlay of every page
+#[cfg(feature = "bundle")]
+fn extract_bundle(extractor: &mut HbsTableExtractor, profile: Option<&str>, out: &str) -> Result<SubstitutionSchedule, Box<dyn Error>> {
+	let extraction = extractor.extract_all()?;
+
+	let profile = match profile {
+		Some(profile) => ExtractionProfile::load(profile)?,
+		None => ExtractionProfile::default(),
+	};
+
+	let overlays = (0..extractor.page_count())
+		.map(|page| overlay(extractor, page))
+		.collect::<Vec<String>>();
+
+	write_bundle(&extraction, &profile, &overlays, File::create(out)?)?;
+
+	Ok(extraction.schedule)
+}
+
+#[cfg(not(feature = "bundle"))]
+fn extract_bundle(_extractor: &mut HbsTableExtractor, _profile: Option<&str>, _out: &str) -> Result<SubstitutionSchedule, Box<dyn Error>> {
+	Err("built without the bundle feature".into())
+}
+
+#[cfg(feature = "profile")]
+fn profile_init(args: &Args) -> Result<(), Box<dyn Error>> {
+	let sample = HbsTableExtractor::new(args.positional[0])?;
+	let mut profile = ExtractionProfile::from_sample(&sample);
+	profile.calibrate(&sample)?;
+
+	match args.positional.get(1) {
+		Some(out) => profile.save(out)?,
+		None => print!("{}", profile.to_toml()?),
+	}
+
+	Ok(())
+}
+
+/// every cell whose text changes between extracting `pdf` with the old and the new profile
+#[cfg(feature = "profile")]
+fn profile_diff_run(args: &Args) -> Result<(), Box<dyn Error>> {
+	let &[pdf, old, new] = args.positional.as_slice() else {
+		return Err(usage().into());
+	};
+
+	let document = Document::load(pdf)?;
+
+	let old_config = ExtractionProfile::load(old)?.config()?;
+	let new_config = ExtractionProfile::load(new)?.config()?;
+
+	let extract = |config| HbsTableExtractor::from_document_with_config(&document, config)?.schedule();
+
+	// a profile that breaks the extraction is what a diff most needs to show, the other side is still listed
+	let (old, new) = match (extract(old_config), extract(new_config)) {
+		(Err(old), Err(new)) => return Err(format!("fails with the old profile: {old}\nfails with the new profile: {new}").into()),
+		(old, new) => {
+			for (side, result) in [("old", &old), ("new", &new)] {
+				if let Err(e) = result {
+					println!("fails with the {side} profile: {e}");
+				}
+			}
+
+			// the failing side counts as a schedule without any class
+			let empty = |date| SubstitutionSchedule { pdf_issue_date: date, entries: Default::default() };
+			let date = old.as_ref().or(new.as_ref()).map_or(0, |s| s.pdf_issue_date);
+
+			(old.unwrap_or_else(|_| empty(date)), new.unwrap_or_else(|_| empty(date)))
+		}
+	};
+
+	let differences = diff_schedules(&old, &new);
+
+	if differences.is_empty() {
+		println!("no differences");
+	}
+
+	let texts = |texts: &Option<Vec<String>>| match texts {
+		Some(texts) => format!("{:?}", texts.join(" ")),
+		None => "-".to_owned(),
+	};
+
+	for difference in &differences {
+		match difference.block {
+			Some(block) => println!("{} block {}: {} -> {}", difference.class, block + 1, texts(&difference.old), texts(&difference.new)),
+			None if difference.new.is_some() => println!("{}: only with the new profile", difference.class),
+			None => println!("{}: only with the old profile", difference.class),
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(not(feature = "profile"))]
+fn profile_init(_args: &Args) -> Result<(), Box<dyn Error>> {
+	Err("built without the profile feature".into())
+}
+
+#[cfg(not(feature = "profile"))]
+fn profile_diff_run(_args: &Args) -> Result<(), Box<dyn Error>> {
+	Err("built without the profile feature".into())
+}
+
+/// the top level commands, with the commands of `profile` as one
+fn command_names() -> Vec<&'static str> {
+	let mut names = COMMANDS.iter()
+		.map(|c| c.name.split(' ').next().expect("split yields at least one part"))
+		.collect::<Vec<&str>>();
+
+	names.dedup();
+	names
+}
+
+/// the flags of all commands with the top level command `name`
+fn flags_of(name: &str) -> Vec<&'static str> {
+	let mut flags = COMMANDS.iter()
+		.filter(|c| c.name.split(' ').next() == Some(name))
+		.flat_map(|c| c.flags.iter().map(|(flag, _)| *flag))
+		.collect::<Vec<&str>>();
+
+	flags.sort_unstable();
+	flags.dedup();
+	flags
+}
+
+/// the second words of the commands under `name`, like "init" for "profile"
+fn subcommands_of(name: &str) -> Vec<&'static str> {
+	COMMANDS.iter()
+		.filter_map(|c| c.name.strip_prefix(name)?.strip_prefix(' '))
+		.collect()
+}
+
+fn self_test(args: &Args) -> Result<(), Box<dyn Error>> {
+	let tabula = args.flag("--tabula");
+
+	let plan = SyntheticPlan::default();
+
+	// through bytes like a downloaded plan, so saving and parsing the pdf is checked too
+	let mut source = Vec::new();
+	plan.to_document().save_to(&mut source)?;
+
+	let mut extractor = HbsTableExtractor::load_from(source.as_slice())?;
+	let mut failed = 0;
+
+	let mut check = |name: &str, result: Result<(), String>| match result {
+		Ok(()) => println!("ok      {name}"),
+		Err(e) => {
+			println!("FAILED  {name}: {e}");
+			failed += 1;
+		}
+	};
+
+	let expected_date = Date::parse_dmy(&plan.date)?;
+
+	check("date", match extractor.extract_date() {
+		Ok(date) if date == expected_date.timestamp_millis() => Ok(()),
+		Ok(date) => Err(format!("expected {expected_date}, got {}", Date::from_timestamp_millis(date).map_or(date.to_string(), |d| d.to_string()))),
+		Err(e) => Err(e.to_string()),
+	});
+
+	let expected_tables = plan.expected_tables();
+
+	check("tables", match extractor.extract_tables() {
+		Ok(tables) if tables == expected_tables => Ok(()),
+		Ok(tables) => Err(format!("expected {expected_tables:?}, got {tables:?}")),
+		Err(e) => Err(e.to_string()),
+	});
+
+	let expected_schedule = plan.expected_schedule()?;
+
+	// every block of every class, so a block lost in the mapping fails too
+	check("schedule", match extractor.schedule() {
+		Ok(schedule) if schedule == expected_schedule => Ok(()),
+		Ok(schedule) => Err(format!("expected {expected_schedule:?}, got {schedule:?}")),
+		Err(e) => Err(e.to_string()),
+	});
+
+	if tabula {
+		#[cfg(feature = "tabula")]
+		{
+			let classes = |schedule: &SubstitutionSchedule| {
+				let mut classes = schedule.entries.keys().cloned().collect::<Vec<String>>();
+				classes.sort();
+				classes
+			};
+
+			let expected_classes = classes(&expected_schedule);
+
+			check("tabula classes", match TabulaParser::schedule_from_pdf(source.as_slice()) {
+				Ok(schedule) if classes(&schedule) == expected_classes => Ok(()),
+				Ok(schedule) => Err(format!("expected {expected_classes:?}, got {:?}", classes(&schedule))),
+				Err(e) => Err(e.to_string()),
+			});
+		}
+
+		#[cfg(not(feature = "tabula"))]
+		check("tabula classes", Err("built without the tabula feature".to_owned()));
+	}
+
+	match failed {
+		0 => Ok(()),
+		_ => Err(format!("{failed} self-test check(s) failed").into()),
+	}
+}
+
+fn completions(args: &Args) -> Result<(), Box<dyn Error>> {
+	let script = match args.positional[0] {
+		"bash" => bash_completions(),
+		// zsh runs the bash completions through its compatibility layer
+		"zsh" => format!("#compdef hbs-extract\nautoload -U +X bashcompinit && bashcompinit\n{}", bash_completions()),
+		"fish" => fish_completions(),
+		_ => return Err(usage().into()),
+	};
+
+	print!("{script}");
+	Ok(())
+}
+
+fn bash_completions() -> String {
+	let mut script = String::from("_hbs_extract() {\n\tlocal cur=${COMP_WORDS[COMP_CWORD]} words=\n\n");
+
+	script += &format!("\tif [ \"$COMP_CWORD\" -eq 1 ]; then\n\t\twords=\"{}\"\n", command_names().join(" "));
+
+	for name in command_names() {
+		let subcommands = subcommands_of(name);
+
+		if !subcommands.is_empty() {
+			script += &format!("\telif [ \"$COMP_CWORD\" -eq 2 ] && [ \"${{COMP_WORDS[1]}}\" = {name} ]; then\n\t\twords=\"{}\"\n", subcommands.join(" "));
+		}
+	}
+
+	script += "\telse\n\t\tcase \"${COMP_WORDS[1]}\" in\n";
+
+	for name in command_names() {
+		script += &format!("\t\t\t{name}) words=\"{}\" ;;\n", flags_of(name).join(" "));
+	}
+
+	script += "\t\tesac\n\n";
+	script += "\t\tif [[ \"$cur\" != -* ]]; then\n\t\t\tCOMPREPLY=($(compgen -f -- \"$cur\"))\n\t\t\treturn\n\t\tfi\n\tfi\n\n";
+	script += "\tCOMPREPLY=($(compgen -W \"$words\" -- \"$cur\"))\n}\n\ncomplete -o filenames -F _hbs_extract hbs-extract\n";
+
+	script
+}
+
+fn fish_completions() -> String {
+	let names = command_names();
+	let mut script = String::new();
+
+	for command in COMMANDS {
+		let mut words = command.name.split(' ');
+		let name = words.next().expect("split yields at least one part");
+		let about = command.about.replace('\'', "\\'");
+
+		match words.next() {
+			Some(subcommand) => script += &format!(
+				"complete -c hbs-extract -f -n '__fish_seen_subcommand_from {name}; and not __fish_seen_subcommand_from {}' -a {subcommand} -d '{about}'\n",
+				subcommands_of(name).join(" "),
+			),
+			None => script += &format!("complete -c hbs-extract -f -n 'not __fish_seen_subcommand_from {}' -a {name} -d '{about}'\n", names.join(" ")),
+		}
+
+		for (flag, value) in command.flags {
+			let required = if value.is_some() { " -r" } else { "" };
+			script += &format!("complete -c hbs-extract -n '__fish_seen_subcommand_from {name}' -l {}{required}\n", flag.trim_start_matches('-'));
+		}
+	}
+
+	script
+}
+
+fn man(_args: &Args) -> Result<(), Box<dyn Error>> {
+	print!("{}", man_page());
+	Ok(())
+}
+
+fn man_page() -> String {
+	// roff reads a leading dot or quote as a request and a plain dash as a hyphen
+	let roff = |text: &str| {
+		let text = text.replace('\\', "\\\\").replace('-', "\\-");
+
+		if text.starts_with(['.', '\'']) {
+			format!("\\&{text}")
+		} else {
+			text
+		}
+	};
+
+	let mut page = format!(
+		".TH HBS\\-EXTRACT 1 \"\" \"hbs\\-extract {}\"\n.SH NAME\nhbs\\-extract \\- extracts substitution schedules from pdf plans\n",
+		env!("CARGO_PKG_VERSION"),
+	);
+
+	page += ".SH SYNOPSIS\n.B hbs\\-extract\n\\fIcommand\\fR [\\fIargs\\fR]\n.SH COMMANDS\n";
+
+	for command in COMMANDS {
+		let flags = command.flags.iter()
+			.map(|(flag, value)| match value {
+				Some(value) => format!(" [\\fB{}\\fR \\fI{}\\fR]", roff(flag), roff(value)),
+				None => format!(" [\\fB{}\\fR]", roff(flag)),
+			})
+			.collect::<String>();
+
+		page += &format!(".TP\n\\fB{}\\fR \\fI{}\\fR{flags}\n{}\n", roff(command.name), roff(command.args), roff(command.about));
+	}
+
+	page
+}
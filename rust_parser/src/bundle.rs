@@ -0,0 +1,39 @@
+use std::error::Error;
+use std::io::Write;
+
+use crate::pipeline::Extraction;
+use crate::profile::ExtractionProfile;
+
+/// zstd level of the bundles, the json compresses well already at the default
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// writes a `.hbsx` bundle, a zstd compressed tar to archive next to a plan or attach to a bug report
+///
+/// it holds `result.json` with the schedule, `diagnostics.json` with the layout problems, `fingerprint.json`
+/// with the layout of the plan, `profile.toml` with the settings that were used and `debug/page-<n>.svg`
+/// for every overlay in `debug_svgs`, pages counting from 0
+pub fn write_bundle<W: Write>(extraction: &Extraction, profile: &ExtractionProfile, debug_svgs: &[String], out: W) -> Result<W, Box<dyn Error>> {
+	let mut tar = tar::Builder::new(zstd::stream::write::Encoder::new(out, COMPRESSION_LEVEL)?);
+
+	let mut entries = vec![
+		("result.json".to_owned(), serde_json::to_vec_pretty(&extraction.schedule)?),
+		("diagnostics.json".to_owned(), serde_json::to_vec_pretty(&extraction.diagnostics)?),
+		("fingerprint.json".to_owned(), serde_json::to_vec_pretty(&extraction.fingerprint)?),
+		("profile.toml".to_owned(), profile.to_toml()?.into_bytes()),
+	];
+
+	for (page, svg) in debug_svgs.iter().enumerate() {
+		entries.push((format!("debug/page-{page}.svg"), svg.clone().into_bytes()));
+	}
+
+	for (path, data) in entries {
+		let mut header = tar::Header::new_gnu();
+		header.set_size(data.len() as u64);
+		header.set_mode(0o644);
+		header.set_cksum();
+
+		tar.append_data(&mut header, path, data.as_slice())?;
+	}
+
+	Ok(tar.into_inner()?.finish()?)
+}
@@ -0,0 +1,42 @@
+use std::ops::RangeInclusive;
+
+use crate::date::{Date, DateFilter};
+
+/// which days have school, to tell an empty weekend apart from a missing plan
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct SchoolCalendar {
+	/// days of the week without school, 1 is monday and 7 sunday, saturday and sunday by default
+	pub weekend: Vec<u32>,
+	/// holidays and single days off, both ends included
+	pub holidays: Vec<RangeInclusive<Date>>,
+}
+
+impl Default for SchoolCalendar {
+	fn default() -> Self {
+		Self {
+			weekend: vec![6, 7],
+			holidays: Vec::new(),
+		}
+	}
+}
+
+impl SchoolCalendar {
+	pub fn is_school_day(&self, date: Date) -> bool {
+		!self.weekend.contains(&date.weekday()) && !self.holidays.iter().any(|h| h.contains(&date))
+	}
+
+	/// the first school day after `date`, `None` if there is none within a year
+	pub fn next_school_day(&self, date: Date) -> Option<Date> {
+		(1..=366)
+			.filter_map(|days| Date::from_timestamp_millis(date.timestamp_millis() + days * 86_400_000))
+			.find(|d| self.is_school_day(*d))
+	}
+
+	/// a filter keeping only school days, for `ExtractorConfig::date_filter`
+	pub fn filter(&self) -> DateFilter {
+		let calendar = self.clone();
+
+		DateFilter::new(move |date| calendar.is_school_day(date))
+	}
+}
@@ -0,0 +1,30 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::error::ExtractionError;
+
+/// shared flag to abort a running extraction, cancelling one clone cancels all of them
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn cancel(&self) {
+		self.0.store(true, Ordering::Relaxed);
+	}
+
+	pub fn is_cancelled(&self) -> bool {
+		self.0.load(Ordering::Relaxed)
+	}
+
+	pub(crate) fn check(&self) -> Result<(), ExtractionError> {
+		if self.is_cancelled() {
+			Err(ExtractionError::Cancelled)
+		} else {
+			Ok(())
+		}
+	}
+}
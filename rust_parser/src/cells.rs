@@ -0,0 +1,133 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::rc::Rc;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::BoundingBox;
+use crate::{HbsTableExtractor, TableColumn, Text};
+
+/// which part of a table a text belongs to, as known from the detected geometry
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum CellKind {
+	/// the topmost cell of a column
+	Header,
+	/// a cell of the column below the "Block" marker, the marker itself included
+	RowLabel,
+	/// any other cell
+	Body,
+}
+
+/// one text fragment of a cell, borrowed from the extractor
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct CellRef<'a> {
+	/// all indices count from 0
+	pub page: usize,
+	pub table: usize,
+	/// from the left of the table, the row labels are column 0 and the classes count from 1
+	pub column: usize,
+	/// row below the header, `None` for the header cell itself
+	pub block: Option<usize>,
+	pub kind: CellKind,
+	pub text: &'a str,
+	pub bbox: BoundingBox,
+	/// the operation that drew the text
+	#[cfg(feature = "provenance")]
+	pub source: crate::Source,
+}
+
+impl HbsTableExtractor {
+	/// every text fragment of every cell, without post-processing, the texts borrowed from the extractor
+	///
+	/// this is not free of allocations: the tables are detected and the columns assembled on copies of the texts
+	/// like in `extract_typed_tables`, it only saves holding every table of the document at once
+	pub fn cells(&self) -> impl Iterator<Item = Result<CellRef<'_>, Box<dyn Error>>> + '_ {
+		self.pages.iter().enumerate().flat_map(move |(page_idx, page)| {
+			// assembling works on copies, this maps them back to the texts owned by the extractor
+			let originals = Rc::new(page.texts().collect::<HashSet<&Text>>());
+
+			let (tables, error) = match page.extract_table_objects(&self.config) {
+				Ok(tables) => (tables, None),
+				Err(e) => (Vec::new(), Some(Err(e))),
+			};
+
+			error.into_iter().chain(tables.into_iter().enumerate().flat_map(move |(table_idx, table)| {
+				let originals = originals.clone();
+
+				let labels = table.label_column().into_iter().map(|column| (0, column, true));
+				let columns = table.extract_columns().into_iter().enumerate().map(|(idx, column)| (idx + 1, column, false));
+
+				labels.chain(columns).flat_map(move |(column_idx, column, label)| {
+					self.column_cells(&originals, page_idx, table_idx, column_idx, column, label)
+				})
+			}))
+		})
+	}
+
+	/// refs of one assembled column, every cell of the label column counts as a row label
+	fn column_cells<'a>(
+		&self,
+		originals: &HashSet<&'a Text>,
+		page: usize,
+		table: usize,
+		column: usize,
+		mut cells: TableColumn,
+		label: bool,
+	) -> Vec<Result<CellRef<'a>, Box<dyn Error>>> {
+		let assembled = match cells.assemble() {
+			Ok(assembled) => assembled,
+			Err(e) => return vec![Err(e)],
+		};
+
+		let mut refs = Vec::new();
+
+		for (idx, texts) in assembled.cells.iter().enumerate() {
+			let bbox = self.exposed(page, assembled.cell_bbox(idx));
+			let kind = match (label, idx) {
+				(true, _) => CellKind::RowLabel,
+				(false, 0) => CellKind::Header,
+				(false, _) => CellKind::Body,
+			};
+
+			for text in texts {
+				let text = originals.get(text).expect("assembled text not on its page");
+
+				refs.push(Ok(CellRef {
+					page,
+					table,
+					column,
+					block: idx.checked_sub(1),
+					kind,
+					text: &text.text,
+					bbox,
+					#[cfg(feature = "provenance")]
+					source: text.source,
+				}));
+			}
+		}
+
+		refs
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{HbsTableExtractor, SyntheticPlan};
+
+	#[test]
+	fn row_labels_and_classes_have_their_own_columns() {
+		let extractor = HbsTableExtractor::from_document(SyntheticPlan::default().to_document()).unwrap();
+		let cells = extractor.cells().collect::<Result<Vec<_>, _>>().unwrap();
+
+		let column = |text: &str| cells.iter().find(|c| c.text == text).map(|c| c.column);
+
+		assert_eq!(column("07:30 - 08:15"), Some(0));
+		assert_eq!(column("5a"), Some(1));
+		assert_eq!(column("Mü"), Some(1));
+		assert_eq!(column("10c"), Some(3));
+	}
+}
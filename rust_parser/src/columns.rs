@@ -0,0 +1,117 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use substitution_common::{SubstitutionColumn, SubstitutionSchedule};
+
+use crate::diff::blocks;
+use crate::{add_column, postprocess, HbsTableExtractor};
+
+/// what became of the column of a class, see `schedule_with_status`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[non_exhaustive]
+pub enum ClassStatus {
+	/// the column has at least one substitution
+	Parsed,
+	/// the column was read but has no substitutions
+	Empty,
+	/// the column could not be read or added to the schedule, e.g. a rejected duplicate
+	Failed(String),
+}
+
+/// a schedule together with the status of every class that has a header in the pdf
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[non_exhaustive]
+pub struct ScheduleReport {
+	pub schedule: SubstitutionSchedule,
+	/// by class name, a page whose tables can't be detected or a table without an end fails under an empty class name.
+	/// a class with columns in several tables has the worst status of them
+	pub classes: BTreeMap<String, ClassStatus>,
+}
+
+impl HbsTableExtractor {
+	/// the columns of the schedule one at a time, each with its class name and the outcome of mapping it
+	///
+	/// a column that fails doesn't stop the others, and as columns are only assembled when they are reached,
+	/// `find` or `take` stop the work early. the class name is the header text if the column can't be assembled,
+	/// a page whose tables can't be detected yields a single error with an empty class name, and so does
+	/// a table without an end, whose classes are unknown
+	pub fn columns_iter(&self) -> impl Iterator<Item = (String, Result<SubstitutionColumn, Box<dyn Error>>)> + '_ {
+		let unpaired = self.unpaired_tables().map(|e| (String::new(), Err(e.into())));
+
+		unpaired.chain(self.pages.iter().flat_map(move |page| {
+			let (tables, error) = match page.extract_table_objects(&self.config) {
+				Ok(tables) => (tables, None),
+				Err(e) => (Vec::new(), Some((String::new(), Err(e)))),
+			};
+
+			error.into_iter().chain(tables.into_iter().flat_map(move |table| {
+				table.extract_columns().into_iter().map(move |mut column| {
+					let header = column.header.text.clone();
+
+					let mut raw = match column.generate_column(&mut Vec::new()) {
+						Ok(raw) => raw,
+						Err(e) => return (header, Err(e)),
+					};
+
+					postprocess::post_process(&self.config.post_processing, &self.config.placeholders, &mut raw);
+
+					match self.config.row_mapper.map_transformed(&raw, &self.config.cell_transforms) {
+						Ok((class, substitutions)) => (class, Ok(substitutions)),
+						Err(e) => (header, Err(e)),
+					}
+				})
+			}))
+		}))
+	}
+
+	/// like `schedule`, but a class whose column fails is left out and reported instead of failing the whole
+	/// schedule, so an empty class can be told apart from one that could not be read
+	pub fn schedule_with_status(&self) -> Result<ScheduleReport, Box<dyn Error>> {
+		let date = self.filtered_date()?;
+
+		let mut entries = HashMap::new();
+		let mut classes = BTreeMap::new();
+
+		for (class, column) in self.columns_iter() {
+			let status = match column {
+				Ok(column) => {
+					let empty = blocks(&column).iter().all(Option::is_none);
+
+					match add_column(&mut entries, class.clone(), column, &self.config) {
+						Ok(()) if empty => ClassStatus::Empty,
+						Ok(()) => ClassStatus::Parsed,
+						Err(e) => ClassStatus::Failed(e.to_string()),
+					}
+				}
+				Err(e) => ClassStatus::Failed(e.to_string()),
+			};
+
+			classes.entry(class)
+				.and_modify(|known: &mut ClassStatus| if status.rank() > known.rank() { *known = status.clone() })
+				.or_insert(status);
+		}
+
+		Ok(ScheduleReport {
+			schedule: SubstitutionSchedule {
+				pdf_issue_date: date.timestamp_millis(),
+				entries,
+			},
+			classes,
+		})
+	}
+}
+
+impl ClassStatus {
+	/// higher is worse, except that a column with substitutions beats an empty one of the same class
+	fn rank(&self) -> u8 {
+		match self {
+			Self::Empty => 0,
+			Self::Parsed => 1,
+			Self::Failed(_) => 2,
+		}
+	}
+}
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use regex::Regex;
+
+use crate::cancellation::CancellationToken;
+#[cfg(feature = "dates")]
+use crate::date::DateFilter;
+#[cfg(feature = "dates")]
+use crate::date_extractor::{DateExtractor, MarkerDate};
+use crate::decoder::{TextDecoder, WinAnsiDecoder};
+use crate::drift::Fingerprint;
+use crate::geometry::{DetectionTolerance, Origin};
+#[cfg(feature = "schedule")]
+use crate::mapping::{CellTransform, DuplicateClasses, RowMapper};
+use crate::marker::{BottomMarker, TopBoundary};
+use crate::postprocess::{default_placeholders, PostProcessRule};
+use crate::progress::{NoProgress, ProgressObserver};
+use crate::section::SectionHeadings;
+
+/// everything that changes how the extractor behaves, `Default` matches the plain `load_from`
+#[derive(Clone)]
+#[non_exhaustive]
+pub struct ExtractorConfig {
+	/// gets notified about the progress of loading and extraction
+	pub observer: Arc<dyn ProgressObserver>,
+	/// checked between pages and columns, extraction fails with `ExtractionError::Cancelled` once it is set
+	pub cancellation: CancellationToken,
+	/// tried in order until one pairs every top marker with a bottom marker and the other way round,
+	/// otherwise the one pairing the most wins. only the "Block" header by default
+	pub top_boundaries: Vec<TopBoundary>,
+	/// slack of the table detection
+	pub tolerance: DetectionTolerance,
+	/// what ends a table, the time of the last block by default
+	pub bottom_marker: BottomMarker,
+	/// how the cells of a column end up in the schedule
+	#[cfg(feature = "schedule")]
+	pub row_mapper: RowMapper,
+	/// what happens to a class with columns in several tables, they are merged by default
+	#[cfg(feature = "schedule")]
+	pub duplicate_classes: DuplicateClasses,
+	/// applied in order to every block cell before it becomes part of a schedule, none by default
+	#[cfg(feature = "schedule")]
+	pub cell_transforms: Vec<CellTransform>,
+	/// applied in order to every cell below the header, none by default
+	pub post_processing: Vec<PostProcessRule>,
+	/// cells below the header whose texts all match one of these (after trimming) count as empty,
+	/// "---" and "frei" by default
+	pub placeholders: Vec<Regex>,
+	/// turns shown strings into text, `WinAnsiDecoder` by default
+	pub decoder: Arc<dyn TextDecoder>,
+	/// origin of the coordinates in reports and typed tables, the pdf's own bottom left by default
+	pub origin: Origin,
+	/// tried in order until one finds the date, only the "Datum: " line by default,
+	/// add `CreationDate` as a fallback for plans that forget it
+	#[cfg(feature = "dates")]
+	pub date_extractors: Vec<Arc<dyn DateExtractor>>,
+	/// days it rejects are skipped before their tables are read, every day is kept by default.
+	/// `SubstitutionPDFExtractor::schedule_from_pdf` always loads with the default config, so it never filters
+	#[cfg(feature = "dates")]
+	pub date_filter: Option<DateFilter>,
+	/// makes loading fail with `ExtractionError::NoContent` if no page has any text or line, off by default
+	pub require_content: bool,
+	/// headings splitting a combined pdf into department sections
+	pub section_headings: SectionHeadings,
+	/// the layout the settings were made for, `extract_all` reports a document whose fonts or tables deviate from it
+	/// with `GeometryViolation::TemplateChanged`, see `Fingerprint::layout_drift`. none by default
+	pub template: Option<Fingerprint>,
+}
+
+impl Default for ExtractorConfig {
+	fn default() -> Self {
+		Self {
+			observer: Arc::new(NoProgress),
+			cancellation: CancellationToken::new(),
+			top_boundaries: vec![TopBoundary::BlockHeader],
+			tolerance: DetectionTolerance::default(),
+			bottom_marker: BottomMarker::default(),
+			#[cfg(feature = "schedule")]
+			row_mapper: RowMapper::default(),
+			#[cfg(feature = "schedule")]
+			duplicate_classes: DuplicateClasses::default(),
+			#[cfg(feature = "schedule")]
+			cell_transforms: Vec::new(),
+			post_processing: Vec::new(),
+			placeholders: default_placeholders(),
+			decoder: Arc::new(WinAnsiDecoder),
+			origin: Origin::default(),
+			section_headings: SectionHeadings::default(),
+			require_content: false,
+			template: None,
+			#[cfg(feature = "dates")]
+			date_extractors: vec![Arc::new(MarkerDate::default())],
+			#[cfg(feature = "dates")]
+			date_filter: None,
+		}
+	}
+}
@@ -0,0 +1,101 @@
+#[cfg(feature = "dates")]
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs::OpenOptions;
+#[cfg(feature = "schedule")]
+use std::io;
+use std::io::Read;
+use std::path::Path;
+
+use lopdf::Document;
+#[cfg(feature = "schedule")]
+use substitution_common::SubstitutionSchedule;
+
+#[cfg(feature = "schedule")]
+use crate::batch::{extract_batch_with_config, extract_directory_with_config, BatchProgress, BatchReport};
+use crate::config::ExtractorConfig;
+#[cfg(feature = "dates")]
+use crate::date::Date;
+#[cfg(feature = "dates")]
+use crate::date_extractor::MarkerDate;
+use crate::labels::Labels;
+#[cfg(feature = "profile")]
+use crate::profile::ExtractionProfile;
+use crate::redact::redact;
+#[cfg(feature = "dates")]
+use crate::split::split_by_date_with_config;
+use crate::HbsTableExtractor;
+
+/// everything the extraction for one school needs, so several schools can be served from one process.
+///
+/// the crate keeps no global state, every entry point is available here with the settings of the context,
+/// progress and cancellation go through `config.observer` and `config.cancellation`
+#[derive(Clone, Default)]
+#[non_exhaustive]
+pub struct ExtractionContext {
+	pub config: ExtractorConfig,
+	/// the words of the exports
+	pub labels: Labels,
+	/// the words `redact` replaces, e.g. teacher abbreviations
+	pub redactions: HashSet<String>,
+}
+
+impl ExtractionContext {
+	pub fn new(config: ExtractorConfig) -> Self {
+		Self {
+			config,
+			..Self::default()
+		}
+	}
+
+	/// the default config with the profile applied
+	#[cfg(feature = "profile")]
+	pub fn from_profile(profile: &ExtractionProfile) -> Result<Self, Box<dyn Error>> {
+		Ok(Self::new(profile.config()?))
+	}
+
+	pub fn open<P: AsRef<Path>>(&self, path: P) -> Result<HbsTableExtractor, Box<dyn Error>> {
+		self.load_from(OpenOptions::new().read(true).open(path)?)
+	}
+
+	pub fn load_from<R: Read>(&self, src: R) -> Result<HbsTableExtractor, Box<dyn Error>> {
+		HbsTableExtractor::load_from_with_config(src, self.config.clone())
+	}
+
+	pub fn from_document(&self, document: &Document) -> Result<HbsTableExtractor, Box<dyn Error>> {
+		HbsTableExtractor::from_document_with_config(document, self.config.clone())
+	}
+
+	#[cfg(feature = "schedule")]
+	pub fn schedule_from_pdf<R: Read>(&self, pdf: R) -> Result<SubstitutionSchedule, Box<dyn Error>> {
+		self.load_from(pdf)?.schedule()
+	}
+
+	/// see `extract_batch`
+	#[cfg(feature = "schedule")]
+	pub fn extract_batch<P, F>(&self, paths: &[P], parallelism: usize, on_progress: F) -> BatchReport
+		where P: AsRef<Path> + Sync, F: Fn(BatchProgress) + Sync
+	{
+		extract_batch_with_config(paths, parallelism, &self.config, on_progress)
+	}
+
+	/// see `extract_directory`
+	#[cfg(feature = "schedule")]
+	pub fn extract_directory<P, F>(&self, dir: P, parallelism: usize, on_progress: F) -> io::Result<BatchReport>
+		where P: AsRef<Path>, F: Fn(BatchProgress) + Sync
+	{
+		extract_directory_with_config(dir, parallelism, &self.config, on_progress)
+	}
+
+	/// see `split_by_date`
+	#[cfg(feature = "dates")]
+	pub fn split_by_date(&self, document: &Document, marker: &MarkerDate) -> Result<BTreeMap<Date, Document>, Box<dyn Error>> {
+		split_by_date_with_config(document, marker, self.config.clone())
+	}
+
+	/// `redact` with the words of the context
+	pub fn redact(&self, document: &mut Document) -> Result<usize, Box<dyn Error>> {
+		redact(document, &self.redactions)
+	}
+}
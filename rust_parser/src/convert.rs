@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use substitution_common::SubstitutionColumn;
+
+use crate::model::{Column, Table};
+
+/// blocks of a day, `SubstitutionColumn` has a field for each
+const BLOCKS: u32 = 6;
+
+/// the one conversion from the cells of blocks 1 to 6 to a `SubstitutionColumn`, shared by the typed tables and
+/// `RowMapper`, so `schedule` and the conversions here agree on which cells become blocks
+pub(crate) fn substitution_column(blocks: Vec<Vec<String>>) -> Result<SubstitutionColumn, Box<dyn Error>> {
+	if blocks.len() != BLOCKS as usize {
+		return Err(format!("{} cells are mapped to blocks, a day has {BLOCKS}", blocks.len()).into());
+	}
+
+	SubstitutionColumn::from_2d_vec(blocks)
+}
+
+/// the first six cells below the header become the blocks, their lines stay in the order of `Cell::sequence`
+impl TryFrom<&Column> for SubstitutionColumn {
+	type Error = Box<dyn Error>;
+
+	fn try_from(column: &Column) -> Result<Self, Self::Error> {
+		let mut blocks = column.cells.iter()
+			.take(BLOCKS as usize)
+			.map(|c| c.lines.clone())
+			.collect::<Vec<Vec<String>>>();
+
+		// a table with missing rows has nothing in the missing blocks
+		blocks.resize(BLOCKS as usize, Vec::new());
+
+		substitution_column(blocks)
+	}
+}
+
+/// one entry per class, the blocks are looked up by the row labels so an extra row doesn't shift them
+impl TryFrom<&Table> for HashMap<String, SubstitutionColumn> {
+	type Error = Box<dyn Error>;
+
+	fn try_from(table: &Table) -> Result<Self, Self::Error> {
+		let mut entries = HashMap::new();
+
+		for (idx, column) in table.columns.iter().enumerate() {
+			let class = column.header.lines.first().ok_or("header cell is empty")?;
+
+			let blocks = (1..=BLOCKS)
+				.map(|block| table.cell(idx, block).map(|c| c.lines.clone()).unwrap_or_default())
+				.collect();
+
+			entries.insert(class.clone(), substitution_column(blocks)?);
+		}
+
+		Ok(entries)
+	}
+}
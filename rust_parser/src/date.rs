@@ -0,0 +1,35 @@
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use crate::error::ExtractionError;
+
+pub use hbs_date::{Date, DateError};
+
+impl From<DateError> for ExtractionError {
+	fn from(e: DateError) -> Self {
+		match e {
+			DateError::Invalid { year, month, day } => Self::InvalidDate { year, month, day },
+			DateError::Malformed(date) => Self::MalformedDate(date),
+			_ => Self::MalformedDate(e.to_string()),
+		}
+	}
+}
+
+/// decides which days of a plan are extracted, see `ExtractorConfig::date_filter`
+#[derive(Clone)]
+pub struct DateFilter(Arc<dyn Fn(Date) -> bool + Send + Sync>);
+
+impl DateFilter {
+	pub fn new<F: Fn(Date) -> bool + Send + Sync + 'static>(keep: F) -> Self {
+		Self(Arc::new(keep))
+	}
+
+	/// the days from the start to the end of `range`, both included
+	pub fn range(range: RangeInclusive<Date>) -> Self {
+		Self::new(move |date| range.contains(&date))
+	}
+
+	pub fn keeps(&self, date: Date) -> bool {
+		(self.0)(date)
+	}
+}
@@ -0,0 +1,142 @@
+use std::error::Error;
+
+use regex::Regex;
+
+use crate::date::Date;
+use crate::{HbsTableExtractor, Text};
+
+/// finds the date a plan is for
+pub trait DateExtractor: Send + Sync {
+	fn extract(&self, extractor: &HbsTableExtractor) -> Result<Date, Box<dyn Error>>;
+
+	/// the date in a single text, for `extract_dates`. `None` by default, for extractors that don't read texts
+	fn extract_from_text(&self, _text: &Text) -> Option<Date> {
+		None
+	}
+}
+
+/// the first text containing `prefix`, the date being its last word like in "Datum: Montag, 12.09.2022"
+#[derive(Clone, Debug)]
+pub struct MarkerDate {
+	pub prefix: String,
+}
+
+impl Default for MarkerDate {
+	fn default() -> Self {
+		Self {
+			prefix: "Datum: ".to_owned(),
+		}
+	}
+}
+
+impl MarkerDate {
+	/// the date of the first of `texts` containing the prefix
+	pub(crate) fn find<'a, I: IntoIterator<Item = &'a Text>>(&self, texts: I) -> Result<Date, Box<dyn Error>> {
+		let date_string = texts.into_iter()
+			.find_map(|t| t.text.find(&self.prefix).map(|idx| &t.text[idx + self.prefix.len()..]))
+			.ok_or_else(|| format!("Couldn't find the date string \"{}\" in PDF", self.prefix))?;
+
+		let date_string = date_string.rsplit(' ').next().ok_or("Date string malformed")?;
+
+		Ok(Date::parse_dmy(date_string)?)
+	}
+}
+
+impl DateExtractor for MarkerDate {
+	fn extract(&self, extractor: &HbsTableExtractor) -> Result<Date, Box<dyn Error>> {
+		self.find(extractor.pages().iter().flat_map(|p| p.texts()))
+	}
+
+	fn extract_from_text(&self, text: &Text) -> Option<Date> {
+		self.find(std::iter::once(text)).ok()
+	}
+}
+
+/// the first match of the regex in any text, its first capture group if it has one, in the format dd.mm.yyyy
+#[derive(Clone, Debug)]
+pub struct RegexDate(pub Regex);
+
+impl DateExtractor for RegexDate {
+	fn extract(&self, extractor: &HbsTableExtractor) -> Result<Date, Box<dyn Error>> {
+		let date_string = extractor.pages().iter()
+			.flat_map(|p| p.texts())
+			.find_map(|t| self.date_string(t))
+			.ok_or_else(|| format!("no text matches {}", self.0))?;
+
+		Ok(Date::parse_dmy(date_string)?)
+	}
+
+	fn extract_from_text(&self, text: &Text) -> Option<Date> {
+		Date::parse_dmy(self.date_string(text)?).ok()
+	}
+}
+
+impl RegexDate {
+	fn date_string<'a>(&self, text: &'a Text) -> Option<&'a str> {
+		let captures = self.0.captures(&text.text)?;
+		Some(captures.get(1).or_else(|| captures.get(0))?.as_str())
+	}
+}
+
+/// the day the pdf was created according to its information dictionary, for plans without a date line
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CreationDate;
+
+impl DateExtractor for CreationDate {
+	fn extract(&self, extractor: &HbsTableExtractor) -> Result<Date, Box<dyn Error>> {
+		let raw = extractor.metadata().creation_date.as_deref().ok_or("the pdf has no creation date")?;
+
+		// "D:YYYYMMDDHHmmSSOHH'mm'", everything after the day is optional and ignored
+		let digits = raw.strip_prefix("D:").unwrap_or(raw);
+		let field = |range: std::ops::Range<usize>| digits.get(range)
+			.and_then(|d| d.parse().ok())
+			.ok_or_else(|| format!("malformed creation date \"{}\"", raw));
+
+		Ok(Date::from_ymd(field(0..4)? as i32, field(4..6)?, field(6..8)?)?)
+	}
+}
+
+impl HbsTableExtractor {
+	/// every date line of the document with the index of its page, top to bottom, without detecting any tables.
+	/// a text counts if one of `ExtractorConfig::date_extractors` reads a date from it on its own
+	pub fn extract_dates(&self) -> Vec<(usize, Date)> {
+		let mut dates = Vec::new();
+
+		for (idx, page) in self.pages().iter().enumerate() {
+			let mut texts = page.texts().collect::<Vec<&Text>>();
+			texts.sort_by_key(|t| std::cmp::Reverse(t.position.y()));
+
+			dates.extend(texts.into_iter()
+				.filter_map(|t| self.config.date_extractors.iter().find_map(|e| e.extract_from_text(t)))
+				.map(|date| (idx, date)));
+		}
+
+		dates
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use super::*;
+	use crate::{ExtractorConfig, SyntheticPlan};
+
+	fn dates(date_extractors: Vec<Arc<dyn DateExtractor>>) -> Vec<(usize, Date)> {
+		let config = ExtractorConfig {
+			date_extractors,
+			..ExtractorConfig::default()
+		};
+
+		HbsTableExtractor::from_document_with_config(&SyntheticPlan::default().to_document(), config).unwrap().extract_dates()
+	}
+
+	#[test]
+	fn dates_are_found_with_the_configured_extractors() {
+		let date = Date::from_ymd(2022, 9, 12).unwrap();
+
+		assert_eq!(dates(vec![Arc::new(MarkerDate { prefix: "Stand: ".to_owned() })]), vec![]);
+		assert_eq!(dates(vec![Arc::new(RegexDate(Regex::new(r"Montag, (\S+)").unwrap()))]), vec![(0, date)]);
+		assert_eq!(dates(vec![Arc::new(CreationDate), Arc::new(MarkerDate::default())]), vec![(0, date)]);
+	}
+}
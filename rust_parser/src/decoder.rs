@@ -0,0 +1,53 @@
+use lopdf::Document;
+
+/// turns the bytes of a shown string into text
+pub trait TextDecoder: Send + Sync {
+	/// `encoding` is the /Encoding name of the font that was active when the string was shown,
+	/// `None` if there was no font or it has no named encoding
+	fn decode(&self, encoding: Option<&str>, bytes: &[u8]) -> String;
+}
+
+/// decodes everything as WinAnsiEncoding no matter the font, the default
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WinAnsiDecoder;
+
+impl TextDecoder for WinAnsiDecoder {
+	fn decode(&self, _encoding: Option<&str>, bytes: &[u8]) -> String {
+		Document::decode_text(Some("WinAnsiEncoding"), bytes)
+	}
+}
+
+/// decodes with the encoding of the active font, falling back to WinAnsiEncoding
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FontEncodingDecoder;
+
+impl TextDecoder for FontEncodingDecoder {
+	fn decode(&self, encoding: Option<&str>, bytes: &[u8]) -> String {
+		Document::decode_text(Some(encoding.unwrap_or("WinAnsiEncoding")), bytes)
+	}
+}
+
+/// a user supplied decoding function, e.g. for fonts with a broken or custom mapping
+pub struct FnDecoder<F>(pub F);
+
+impl<F: Fn(Option<&str>, &[u8]) -> String + Send + Sync> TextDecoder for FnDecoder<F> {
+	fn decode(&self, encoding: Option<&str>, bytes: &[u8]) -> String {
+		(self.0)(encoding, bytes)
+	}
+}
+
+/// strings starting with the UTF-16BE byte order mark are text strings no matter the font,
+/// everything else goes to the configured decoder
+pub(crate) fn decode_shown(decoder: &dyn TextDecoder, encoding: Option<&str>, bytes: &[u8]) -> String {
+	match bytes.strip_prefix(&[0xFE, 0xFF]) {
+		Some(utf16) => {
+			let units = utf16.chunks_exact(2)
+				.map(|pair| u16::from_be_bytes([pair[0], pair[1]]));
+
+			char::decode_utf16(units)
+				.map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+				.collect()
+		}
+		None => decoder.decode(encoding, bytes),
+	}
+}
@@ -0,0 +1,253 @@
+use std::collections::BTreeSet;
+
+use regex::Regex;
+use substitution_common::{Substitution, SubstitutionColumn, SubstitutionSchedule};
+
+/// a class whose substitutions differ between two schedules
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CellDifference {
+	pub class: String,
+	/// `None` if the class only exists in one of the schedules and has no substitution there
+	pub block: Option<usize>,
+	/// the texts in the old schedule, `None` if the cell or class is missing there
+	pub old: Option<Vec<String>>,
+	/// the texts in the new schedule, `None` if the cell or class is missing there
+	pub new: Option<Vec<String>>,
+}
+
+/// every cell that differs between the schedules, sorted by class and block
+pub fn diff_schedules(old: &SubstitutionSchedule, new: &SubstitutionSchedule) -> Vec<CellDifference> {
+	let mut classes = old.entries.keys().chain(new.entries.keys()).collect::<Vec<&String>>();
+	classes.sort();
+	classes.dedup();
+
+	let mut differences = Vec::new();
+
+	for class in classes {
+		match (old.entries.get(class), new.entries.get(class)) {
+			(Some(old_column), Some(new_column)) => {
+				for (block, (old_block, new_block)) in blocks(old_column).iter().zip(blocks(new_column)).enumerate() {
+					if *old_block != new_block {
+						differences.push(CellDifference {
+							class: class.clone(),
+							block: Some(block),
+							old: old_block.map(|s| s.0.clone()),
+							new: new_block.map(|s| s.0.clone()),
+						});
+					}
+				}
+			}
+			(old_column, new_column) => {
+				let before = differences.len();
+
+				// every substitution of a class that appeared or vanished is a difference of its own
+				for (block, (old_block, new_block)) in old_column.map(blocks).unwrap_or_default().into_iter()
+					.zip(new_column.map(blocks).unwrap_or_default())
+					.enumerate()
+					.filter(|(_, (old_block, new_block))| old_block.is_some() || new_block.is_some())
+				{
+					differences.push(CellDifference {
+						class: class.clone(),
+						block: Some(block),
+						old: old_block.map(|s| s.0.clone()),
+						new: new_block.map(|s| s.0.clone()),
+					});
+				}
+
+				// a class without any substitution still appeared or vanished
+				if differences.len() == before {
+					differences.push(CellDifference {
+						class: class.clone(),
+						block: None,
+						old: old_column.map(|_| Vec::new()),
+						new: new_column.map(|_| Vec::new()),
+					});
+				}
+			}
+		}
+	}
+
+	differences
+}
+
+/// how urgently a change should reach the people it affects, ordered from least to most urgent
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Severity {
+	/// update silently
+	Silent,
+	/// worth a notification
+	Notify,
+	/// worth a push notification right away
+	Urgent,
+}
+
+/// what kind of change a `CellDifference` is, see `ChangeClassifier`
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum ChangeKind {
+	NewCancellation,
+	CancellationRevoked,
+	RoomChanged,
+	/// the text changed in some other way, e.g. a note was reworded
+	NoteEdited,
+	/// a cell or class that was empty or missing has a substitution now
+	SubstitutionAdded,
+	/// a cell or class that had a substitution is empty or missing now
+	SubstitutionRemoved,
+}
+
+impl ChangeKind {
+	pub fn severity(&self) -> Severity {
+		match self {
+			Self::NewCancellation | Self::CancellationRevoked => Severity::Urgent,
+			Self::RoomChanged | Self::SubstitutionAdded | Self::SubstitutionRemoved => Severity::Notify,
+			Self::NoteEdited => Severity::Silent,
+		}
+	}
+}
+
+/// a difference with its kind and severity
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClassifiedDifference {
+	pub difference: CellDifference,
+	pub kind: ChangeKind,
+	pub severity: Severity,
+}
+
+/// tells the kinds of changes apart by the texts of the cells, there is no structure to go by
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ChangeClassifier {
+	/// a cell is a cancellation if one of its texts matches, "Entfall" and "entfällt" by default
+	pub cancellation: Regex,
+	/// matches the room codes in a text, like "R104" or "B2.05"
+	pub room: Regex,
+}
+
+impl Default for ChangeClassifier {
+	fn default() -> Self {
+		Self {
+			cancellation: Regex::new(r"(?i)\b(entfall|entfällt)\b").expect("invalid builtin regex"),
+			room: Regex::new(r"\b[A-Z]{1,2}\d+(\.\d+)?\b").expect("invalid builtin regex"),
+		}
+	}
+}
+
+impl ChangeClassifier {
+	pub fn classify(&self, difference: &CellDifference) -> ChangeKind {
+		// a class without any substitution appeared or vanished, the ones with substitutions come
+		// block by block and are told apart by their texts like any other cell
+		if difference.block.is_none() {
+			return match difference.new {
+				Some(_) => ChangeKind::SubstitutionAdded,
+				None => ChangeKind::SubstitutionRemoved,
+			};
+		}
+
+		let old = difference.old.as_deref().unwrap_or_default();
+		let new = difference.new.as_deref().unwrap_or_default();
+
+		let cancelled = |texts: &[String]| texts.iter().any(|t| self.cancellation.is_match(t));
+
+		let rooms = |texts: &[String]| texts.iter()
+			.flat_map(|t| self.room.find_iter(t).map(|m| m.as_str().to_owned()))
+			.collect::<BTreeSet<String>>();
+
+		match (cancelled(old), cancelled(new)) {
+			(false, true) => ChangeKind::NewCancellation,
+			(true, false) => ChangeKind::CancellationRevoked,
+			_ if old.is_empty() => ChangeKind::SubstitutionAdded,
+			_ if new.is_empty() => ChangeKind::SubstitutionRemoved,
+			_ if rooms(old) != rooms(new) => ChangeKind::RoomChanged,
+			_ => ChangeKind::NoteEdited,
+		}
+	}
+
+	/// classifies every difference, the most urgent first and otherwise in the order of `differences`
+	pub fn classify_all(&self, differences: Vec<CellDifference>) -> Vec<ClassifiedDifference> {
+		let mut classified = differences.into_iter()
+			.map(|difference| {
+				let kind = self.classify(&difference);
+
+				ClassifiedDifference {
+					difference,
+					kind,
+					severity: kind.severity(),
+				}
+			})
+			.collect::<Vec<ClassifiedDifference>>();
+
+		classified.sort_by_key(|c| std::cmp::Reverse(c.severity));
+		classified
+	}
+}
+
+/// the blocks of a column in order
+pub(crate) fn blocks(column: &SubstitutionColumn) -> [Option<&Substitution>; 6] {
+	[
+		column.block_0.as_ref(),
+		column.block_1.as_ref(),
+		column.block_2.as_ref(),
+		column.block_3.as_ref(),
+		column.block_4.as_ref(),
+		column.block_5.as_ref(),
+	]
+}
+
+/// like `blocks` but for changing them
+pub(crate) fn blocks_mut(column: &mut SubstitutionColumn) -> [&mut Option<Substitution>; 6] {
+	[
+		&mut column.block_0,
+		&mut column.block_1,
+		&mut column.block_2,
+		&mut column.block_3,
+		&mut column.block_4,
+		&mut column.block_5,
+	]
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+
+	use super::*;
+
+	fn schedule(classes: &[(&str, &[&[&str]])]) -> SubstitutionSchedule {
+		let entries = classes.iter()
+			.map(|(class, cells)| {
+				let blocks = cells.iter().map(|c| c.iter().map(|l| (*l).to_owned()).collect()).collect();
+				((*class).to_owned(), SubstitutionColumn::from_2d_vec(blocks).unwrap())
+			})
+			.collect::<HashMap<String, SubstitutionColumn>>();
+
+		SubstitutionSchedule {
+			pdf_issue_date: 0,
+			entries,
+		}
+	}
+
+	#[test]
+	fn new_class_keeps_its_texts() {
+		let old = schedule(&[]);
+		let new = schedule(&[("5a", &[&[], &[], &["Entfall"], &[], &[], &[]])]);
+
+		assert_eq!(diff_schedules(&old, &new), vec![CellDifference {
+			class: "5a".to_owned(),
+			block: Some(2),
+			old: None,
+			new: Some(vec!["Entfall".to_owned()]),
+		}]);
+	}
+
+	#[test]
+	fn cancellation_of_a_new_class_is_urgent() {
+		let old = schedule(&[]);
+		let new = schedule(&[("5a", &[&[], &[], &["Entfall"], &[], &[], &[]])]);
+
+		let classified = ChangeClassifier::default().classify_all(diff_schedules(&old, &new));
+
+		assert_eq!(classified.len(), 1);
+		assert_eq!(classified[0].kind, ChangeKind::NewCancellation);
+		assert_eq!(classified[0].severity, Severity::Urgent);
+	}
+}
@@ -0,0 +1,277 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::HbsTableExtractor;
+
+/// layout of a known-good pdf, store it and compare later ones against it with `drift`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Fingerprint {
+	pub pages: Vec<PageFingerprint>,
+	/// see `DocumentMetadata::fonts`, missing in fingerprints stored before fonts were recorded
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub fonts: Vec<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PageFingerprint {
+	/// y of the "Block" headers
+	pub top_markers: Vec<i64>,
+	/// y of the times of the last block
+	pub bottom_markers: Vec<i64>,
+	pub tables: Vec<TableFingerprint>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TableFingerprint {
+	pub column_count: usize,
+	/// distance between the row separators of the first column that could be assembled, top to bottom
+	pub row_spacing: Vec<i64>,
+}
+
+/// how far a fingerprint may move before it counts as a deviation
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct DriftTolerance {
+	pub marker_position: i64,
+	pub row_spacing: i64,
+}
+
+impl Default for DriftTolerance {
+	fn default() -> Self {
+		Self {
+			marker_position: 4,
+			row_spacing: 2,
+		}
+	}
+}
+
+/// a significant difference between a fingerprint and its baseline
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum Deviation {
+	PageCount { baseline: usize, current: usize },
+	Fonts { baseline: Vec<String>, current: Vec<String> },
+	MarkerCount { page: usize, baseline: usize, current: usize },
+	MarkerMoved { page: usize, marker: usize, baseline: i64, current: i64 },
+	TableCount { page: usize, baseline: usize, current: usize },
+	ColumnCount { page: usize, table: usize, baseline: usize, current: usize },
+	RowCount { page: usize, table: usize, baseline: usize, current: usize },
+	RowSpacing { page: usize, table: usize, row: usize, baseline: i64, current: i64 },
+}
+
+impl Display for Deviation {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::PageCount { baseline, current } =>
+				write!(f, "page count changed from {baseline} to {current}"),
+			Self::Fonts { baseline, current } =>
+				write!(f, "fonts changed from {} to {}", baseline.join(", "), current.join(", ")),
+			Self::MarkerCount { page, baseline, current } =>
+				write!(f, "page {page}: marker count changed from {baseline} to {current}"),
+			Self::MarkerMoved { page, marker, baseline, current } =>
+				write!(f, "page {page}: marker {marker} moved from y {baseline} to y {current}"),
+			Self::TableCount { page, baseline, current } =>
+				write!(f, "page {page}: table count changed from {baseline} to {current}"),
+			Self::ColumnCount { page, table, baseline, current } =>
+				write!(f, "page {page}, table {table}: column count changed from {baseline} to {current}"),
+			Self::RowCount { page, table, baseline, current } =>
+				write!(f, "page {page}, table {table}: row count changed from {baseline} to {current}"),
+			Self::RowSpacing { page, table, row, baseline, current } =>
+				write!(f, "page {page}, table {table}: height of row {row} changed from {baseline} to {current}"),
+		}
+	}
+}
+
+impl HbsTableExtractor {
+	/// measures the layout of the document without extracting any text
+	pub fn fingerprint(&self) -> Result<Fingerprint, Box<dyn Error>> {
+		let mut pages = Vec::new();
+
+		for page in &self.pages {
+			self.config.cancellation.check()?;
+
+			let mut tables = Vec::new();
+
+			for table in page.extract_table_objects(&self.config)? {
+				let columns = table.extract_columns();
+				let column_count = columns.len();
+
+				let row_spacing = columns.into_iter()
+					.find_map(|mut c| c.assemble().ok())
+					.map(|c| c.rulings.windows(2).map(|w| w[0] - w[1]).collect())
+					.unwrap_or_default();
+
+				tables.push(TableFingerprint {
+					column_count,
+					row_spacing,
+				});
+			}
+
+			pages.push(PageFingerprint {
+				top_markers: page.top_markers(&self.config.tolerance),
+				bottom_markers: page.bottom_markers(&self.config.bottom_marker),
+				tables,
+			});
+		}
+
+		Ok(Fingerprint {
+			pages,
+			fonts: self.metadata.fonts.clone(),
+		})
+	}
+
+	/// how the layout of the document deviates from `ExtractorConfig::template`, empty if no template is set
+	pub fn template_drift(&self) -> Result<Vec<Deviation>, Box<dyn Error>> {
+		match &self.config.template {
+			Some(template) => Ok(self.fingerprint()?.layout_drift(template, &DriftTolerance::default())),
+			None => Ok(Vec::new()),
+		}
+	}
+}
+
+impl Fingerprint {
+	/// everything that differs from `baseline` by more than the tolerance
+	pub fn drift(&self, baseline: &Fingerprint, tolerance: &DriftTolerance) -> Vec<Deviation> {
+		let mut deviations = Vec::new();
+
+		if self.pages.len() != baseline.pages.len() {
+			deviations.push(Deviation::PageCount { baseline: baseline.pages.len(), current: self.pages.len() });
+		}
+
+		// an old baseline without fonts can't tell
+		if !baseline.fonts.is_empty() && self.fonts != baseline.fonts {
+			deviations.push(Deviation::Fonts { baseline: baseline.fonts.clone(), current: self.fonts.clone() });
+		}
+
+		for (page, (current, baseline)) in self.pages.iter().zip(&baseline.pages).enumerate() {
+			for (markers, baseline_markers) in [
+				(&current.top_markers, &baseline.top_markers),
+				(&current.bottom_markers, &baseline.bottom_markers),
+			] {
+				if markers.len() != baseline_markers.len() {
+					deviations.push(Deviation::MarkerCount { page, baseline: baseline_markers.len(), current: markers.len() });
+				}
+
+				for (marker, (current, baseline)) in markers.iter().zip(baseline_markers).enumerate() {
+					if (current - baseline).abs() > tolerance.marker_position {
+						deviations.push(Deviation::MarkerMoved { page, marker, baseline: *baseline, current: *current });
+					}
+				}
+			}
+
+			if current.tables.len() != baseline.tables.len() {
+				deviations.push(Deviation::TableCount { page, baseline: baseline.tables.len(), current: current.tables.len() });
+			}
+
+			for (table, (current, baseline)) in current.tables.iter().zip(&baseline.tables).enumerate() {
+				if current.column_count != baseline.column_count {
+					deviations.push(Deviation::ColumnCount { page, table, baseline: baseline.column_count, current: current.column_count });
+				}
+
+				if current.row_spacing.len() != baseline.row_spacing.len() {
+					deviations.push(Deviation::RowCount { page, table, baseline: baseline.row_spacing.len(), current: current.row_spacing.len() });
+				}
+
+				for (row, (current, baseline)) in current.row_spacing.iter().zip(&baseline.row_spacing).enumerate() {
+					if (current - baseline).abs() > tolerance.row_spacing {
+						deviations.push(Deviation::RowSpacing { page, table, row, baseline: *baseline, current: *current });
+					}
+				}
+			}
+		}
+
+		deviations
+	}
+
+	/// the differences from `template` that mean the layout changed, not just how much is on the plan
+	///
+	/// pages, tables and markers come and go with the number of substitutions of the day, so only the fonts,
+	/// columns beyond the widest table of the template and the rows of every table against the first table
+	/// of the template count
+	pub fn layout_drift(&self, template: &Fingerprint, tolerance: &DriftTolerance) -> Vec<Deviation> {
+		let mut deviations = Vec::new();
+
+		if !template.fonts.is_empty() && self.fonts != template.fonts {
+			deviations.push(Deviation::Fonts { baseline: template.fonts.clone(), current: self.fonts.clone() });
+		}
+
+		let template_tables = template.pages.iter().flat_map(|p| &p.tables);
+		let widest = template_tables.clone().map(|t| t.column_count).max();
+		let rows = template_tables.map(|t| &t.row_spacing).find(|r| !r.is_empty());
+
+		for (page, current) in self.pages.iter().enumerate() {
+			for (table, current) in current.tables.iter().enumerate() {
+				if let Some(widest) = widest.filter(|w| current.column_count > *w) {
+					deviations.push(Deviation::ColumnCount { page, table, baseline: widest, current: current.column_count });
+				}
+
+				// a table whose rows couldn't be measured has nothing to compare
+				let rows = match rows.filter(|_| !current.row_spacing.is_empty()) {
+					Some(rows) => rows,
+					None => continue,
+				};
+
+				if current.row_spacing.len() != rows.len() {
+					deviations.push(Deviation::RowCount { page, table, baseline: rows.len(), current: current.row_spacing.len() });
+				}
+
+				for (row, (current, baseline)) in current.row_spacing.iter().zip(rows).enumerate() {
+					if (current - baseline).abs() > tolerance.row_spacing {
+						deviations.push(Deviation::RowSpacing { page, table, row, baseline: *baseline, current: *current });
+					}
+				}
+			}
+		}
+
+		deviations
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn fingerprint(pages: &[&[(usize, &[i64])]]) -> Fingerprint {
+		Fingerprint {
+			pages: pages.iter()
+				.map(|tables| PageFingerprint {
+					top_markers: tables.iter().enumerate().map(|(i, _)| 700 - 300 * i as i64).collect(),
+					bottom_markers: tables.iter().enumerate().map(|(i, _)| 450 - 300 * i as i64).collect(),
+					tables: tables.iter()
+						.map(|(column_count, row_spacing)| TableFingerprint {
+							column_count: *column_count,
+							row_spacing: row_spacing.to_vec(),
+						})
+						.collect(),
+				})
+				.collect(),
+			fonts: vec!["Arial".to_string()],
+		}
+	}
+
+	#[test]
+	fn busier_day_keeps_the_layout() {
+		let template = fingerprint(&[&[(7, &[40; 6])]]);
+		let plan = fingerprint(&[&[(7, &[40; 6]), (3, &[41; 6])], &[(5, &[40; 6])]]);
+
+		assert_eq!(plan.layout_drift(&template, &DriftTolerance::default()), vec![]);
+	}
+
+	#[test]
+	fn taller_rows_are_a_new_layout() {
+		let template = fingerprint(&[&[(7, &[40; 6])]]);
+		let plan = fingerprint(&[&[(8, &[40, 40, 50, 40, 40, 40])]]);
+
+		assert_eq!(plan.layout_drift(&template, &DriftTolerance::default()), vec![
+			Deviation::ColumnCount { page: 0, table: 0, baseline: 7, current: 8 },
+			Deviation::RowSpacing { page: 0, table: 0, row: 2, baseline: 40, current: 50 },
+		]);
+	}
+}
@@ -0,0 +1,39 @@
+use std::error::Error;
+use std::io::Read;
+
+use lopdf::content::Operation;
+use lopdf::{Document, Object};
+
+/// one line per operation like "100.5 700 Td", as it is in the content streams of the page
+///
+/// with `redact` every string operand is replaced by its length, so the listing can be shared
+/// without giving away the names in the plan
+pub fn dump_operations<R: Read>(src: R, page: usize, redact: bool) -> Result<Vec<String>, Box<dyn Error>> {
+	let document = Document::load_from(src)?;
+
+	let page_id = document.page_iter()
+		.nth(page)
+		.ok_or_else(|| format!("the document has no page {} (pages count from 0)", page))?;
+
+	let mut lines = Vec::new();
+
+	for object_id in document.get_page_contents(page_id) {
+		if let Ok(stream) = document.get_object(object_id)?.as_stream() {
+			let mut stream = stream.to_owned();
+			stream.decompress();
+
+			lines.extend(stream.decode_content()?.operations.iter().map(|op| format_operation(op, redact)));
+		}
+	}
+
+	Ok(lines)
+}
+
+pub(crate) fn format_operation(op: &Operation, redact: bool) -> String {
+	op.operands.iter()
+		.map(|o| match o {
+			Object::String(bytes, _) if redact => format!("(<{} bytes>) ", bytes.len()),
+			_ => format!("{:?} ", o),
+		})
+		.collect::<String>() + &op.operator
+}
@@ -0,0 +1,42 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// the wrapper of all json the command line tools print, so an archived output can be traced back
+/// to the extractor and settings that produced it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct JsonEnvelope<T> {
+	/// the version of this crate
+	pub tool_version: String,
+	/// sha256 of the profile file in hex, `None` for the default settings
+	pub profile_hash: Option<String>,
+	/// sha256 of the pdf in hex
+	pub source_sha256: String,
+	/// in milliseconds since the epoch
+	pub extracted_at: i64,
+	pub result: T,
+}
+
+impl<T> JsonEnvelope<T> {
+	/// wraps `result`, extracted now from `source` with the profile file `profile`
+	pub fn new(result: T, source: &[u8], profile: Option<&[u8]>) -> Self {
+		let extracted_at = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map(|d| d.as_millis() as i64)
+			.unwrap_or_default();
+
+		Self {
+			tool_version: env!("CARGO_PKG_VERSION").to_owned(),
+			profile_hash: profile.map(sha256),
+			source_sha256: sha256(source),
+			extracted_at,
+			result,
+		}
+	}
+}
+
+fn sha256(data: &[u8]) -> String {
+	format!("{:x}", Sha256::digest(data))
+}
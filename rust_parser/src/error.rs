@@ -0,0 +1,29 @@
+use thiserror::Error;
+
+/// errors callers may want to tell apart, everything else is reported as a plain message
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ExtractionError {
+	#[error("extraction was cancelled")]
+	Cancelled,
+	#[error("{day}.{month}.{year} is not a valid date")]
+	InvalidDate { year: i32, month: u32, day: u32 },
+	#[error("\"{0}\" is not a date in the format dd.mm.yyyy")]
+	MalformedDate(String),
+	/// `page` counts from 0, `operation` is the index across the content streams of the page like in `dump_operations`,
+	/// `window` holds the operations around it
+	#[error("page {page}, operation {operation}: {message} (near `{window}`)")]
+	ContentStream { page: usize, operation: usize, window: String, message: String },
+	#[error("the pdf has no pages or no text and lines on them")]
+	NoContent,
+	#[error("class \"{0}\" has a column in more than one table")]
+	DuplicateClass(String),
+	/// `markers_found` counts the table areas whose columns could not be found, 0 if no page had a marker at all
+	#[error("no tables detected on {pages_scanned} pages, {markers_found} table markers found")]
+	NoTablesDetected { pages_scanned: usize, markers_found: usize },
+	/// `y` is in the coordinates of `ExtractorConfig::origin`
+	#[error("page {page}: the table starting at y {y} has no bottom marker, its classes are missing")]
+	UnpairedMarker { page: usize, y: i64 },
+	#[error("the plan for {day:02}.{month:02}.{year} is rejected by the date filter")]
+	FilteredDate { year: i32, month: u32, day: u32 },
+}
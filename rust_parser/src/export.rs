@@ -0,0 +1,91 @@
+use std::io::{self, Write};
+
+use substitution_common::SubstitutionSchedule;
+
+use crate::date::Date;
+use crate::diff::blocks;
+use crate::labels::Labels;
+
+/// writes the schedule as csv with one row per class and block that has a substitution, sorted by class and block
+///
+/// the columns are `date,class,block,text`, the date as yyyy-mm-dd, blocks counting from 1 like in the plan
+/// and the lines of a cell joined by spaces
+pub fn write_long_csv<W: Write>(schedule: &SubstitutionSchedule, mut out: W) -> io::Result<()> {
+	writeln!(out, "date,class,block,text")?;
+
+	for record in long_records(schedule) {
+		writeln!(out, "{},{},{},{}", record.date, csv_field(&record.class), record.block, csv_field(&record.text))?;
+	}
+
+	Ok(())
+}
+
+/// writes the schedule as a markdown table with one row per class and block that has a substitution,
+/// headed by the date
+pub fn write_markdown<W: Write>(schedule: &SubstitutionSchedule, labels: &Labels, mut out: W) -> io::Result<()> {
+	let records = long_records(schedule);
+
+	match records.first() {
+		Some(first) => writeln!(out, "## {} {}\n", labels.date, first.date)?,
+		None => return writeln!(out, "{}", labels.no_substitutions),
+	}
+
+	writeln!(out, "| {} | {} | {} |", labels.class, labels.block, labels.substitution)?;
+	writeln!(out, "| --- | --- | --- |")?;
+
+	for record in records {
+		writeln!(out, "| {} | {} | {} |", markdown_cell(&record.class), record.block, markdown_cell(&record.text))?;
+	}
+
+	Ok(())
+}
+
+/// a row of the long format
+pub(crate) struct LongRecord {
+	/// yyyy-mm-dd
+	pub(crate) date: String,
+	pub(crate) class: String,
+	/// counting from 1
+	pub(crate) block: i32,
+	pub(crate) text: String,
+}
+
+/// the non-empty cells of the schedule sorted by class and block
+pub(crate) fn long_records(schedule: &SubstitutionSchedule) -> Vec<LongRecord> {
+	let date = Date::from_timestamp_millis(schedule.pdf_issue_date)
+		.map(|d| format!("{:04}-{:02}-{:02}", d.year(), d.month(), d.day()))
+		.unwrap_or_default();
+
+	let mut classes = schedule.entries.iter().collect::<Vec<_>>();
+	classes.sort_by_key(|(class, _)| *class);
+
+	let mut records = Vec::new();
+
+	for (class, column) in classes {
+		for (block, substitution) in blocks(column).iter().enumerate() {
+			match substitution {
+				Some(s) if !s.0.is_empty() => records.push(LongRecord {
+					date: date.clone(),
+					class: class.clone(),
+					block: block as i32 + 1,
+					text: s.0.join(" "),
+				}),
+				_ => (),
+			}
+		}
+	}
+
+	records
+}
+
+fn csv_field(field: &str) -> String {
+	if field.contains([',', '"', '\n', '\r']) {
+		format!("\"{}\"", field.replace('"', "\"\""))
+	} else {
+		field.to_owned()
+	}
+}
+
+fn markdown_cell(text: &str) -> String {
+	text.replace('|', "\\|")
+}
@@ -0,0 +1,207 @@
+//! points, lines and comparisons with a tolerance, coordinates are whole pdf units so a tolerance of 0 means exact
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// `a` and `b` are at most `tolerance` apart
+pub fn approx_eq(a: i64, b: i64, tolerance: i64) -> bool {
+	(a - b).abs() <= tolerance
+}
+
+/// `value` lies strictly between `lower` and `upper`, each bound moved outwards by `tolerance`
+pub fn within(value: i64, lower: i64, upper: i64, tolerance: i64) -> bool {
+	value > lower - tolerance && value < upper + tolerance
+}
+
+/// the ranges `a` and `b`, given as (start, end), share more than `tolerance` units
+pub fn overlaps_1d(a: (i64, i64), b: (i64, i64), tolerance: i64) -> bool {
+	a.0 + tolerance < b.1 && b.0 + tolerance < a.1
+}
+
+/// `point` is inside `rect` or at most `tolerance` outside of it
+pub fn point_in_rect(point: (i64, i64), rect: &BoundingBox, tolerance: i64) -> bool {
+	let (left, right) = (rect.left.min(rect.right), rect.left.max(rect.right));
+	let (bottom, top) = (rect.bottom.min(rect.top), rect.bottom.max(rect.top));
+
+	point.0 >= left - tolerance && point.0 <= right + tolerance
+		&& point.1 >= bottom - tolerance && point.1 <= top + tolerance
+}
+
+/// slack of the table detection, see `ExtractionProfile::calibrate` for deriving it from a sample
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[non_exhaustive]
+pub struct DetectionTolerance {
+	/// how far a text may sit above or below the baseline of the "Block" header and still be on the header row
+	pub header_baseline: i64,
+	/// added above the header and below the bottom line of a table so the objects on its edge belong to it
+	pub table_margin: i64,
+	/// collinear pieces of a line at most this far apart are joined into one line while loading,
+	/// pieces that overlap or touch are always joined
+	pub segment_gap: i64,
+	/// how far the control points of a curve may be from the line between its ends for the curve to count as
+	/// that line, more curved ones are ignored and reported by `validate_geometry`
+	pub curve_flatness: i64,
+	/// how far below its top marker the bottom marker of a table may be, markers without a partner in reach
+	/// like the "15:15" in the example of a legend are left out and reported by `validate_geometry`
+	pub max_table_height: i64,
+}
+
+impl Default for DetectionTolerance {
+	fn default() -> Self {
+		Self {
+			header_baseline: 2,
+			table_margin: 4,
+			segment_gap: 0,
+			curve_flatness: 1,
+			max_table_height: 700,
+		}
+	}
+}
+
+/// where y = 0 is in the coordinates the extractor hands out
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum Origin {
+	/// the bottom of the page, y grows upwards like in the pdf itself
+	#[default]
+	BottomLeft,
+	/// the top of the page, y grows downwards like on a screen
+	TopLeft,
+}
+
+impl Origin {
+	/// moves a y in pdf coordinates to this origin
+	pub(crate) fn y(self, y: i64, page_height: i64) -> i64 {
+		match self {
+			Self::BottomLeft => y,
+			Self::TopLeft => page_height - y,
+		}
+	}
+}
+
+/// axis aligned rectangle in pdf units, `top` and `bottom` are the upper and lower edge on the page
+/// whatever the `Origin` is
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BoundingBox {
+	pub left: i64,
+	pub bottom: i64,
+	pub right: i64,
+	pub top: i64,
+}
+
+impl BoundingBox {
+	/// the smallest box containing all points, `None` if there are none
+	pub(crate) fn around<I: IntoIterator<Item = (i64, i64)>>(points: I) -> Option<Self> {
+		points.into_iter().fold(None, |bbox, (x, y)| Some(match bbox {
+			None => Self { left: x, bottom: y, right: x, top: y },
+			Some(b) => Self {
+				left: b.left.min(x),
+				bottom: b.bottom.min(y),
+				right: b.right.max(x),
+				top: b.top.max(y),
+			},
+		}))
+	}
+
+	/// the area both boxes cover, with `left` > `right` or `bottom` > `top` if they don't overlap
+	pub(crate) fn intersection(&self, other: &Self) -> Self {
+		Self {
+			left: self.left.max(other.left),
+			bottom: self.bottom.max(other.bottom),
+			right: self.right.min(other.right),
+			top: self.top.min(other.top),
+		}
+	}
+
+	/// the boxes share at least a point, never true for an empty intersection
+	pub(crate) fn touches(&self, other: &Self) -> bool {
+		self.left <= other.right && other.left <= self.right
+			&& self.bottom <= other.top && other.bottom <= self.top
+	}
+
+	pub(crate) fn with_origin(self, origin: Origin, page_height: i64) -> Self {
+		Self {
+			left: self.left,
+			bottom: origin.y(self.bottom, page_height),
+			right: self.right,
+			top: origin.y(self.top, page_height),
+		}
+	}
+}
+
+/// a position on the page in pdf units
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Point {
+	pub x: i64,
+	pub y: i64,
+}
+
+impl Point {
+	pub fn new(x: i64, y: i64) -> Self {
+		Self { x, y }
+	}
+
+	pub fn x(self) -> i64 {
+		self.x
+	}
+
+	pub fn y(self) -> i64 {
+		self.y
+	}
+}
+
+/// a straight line from `start` to `end` as drawn in the pdf
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Line {
+	pub start: Point,
+	pub end: Point,
+}
+
+impl Line {
+	pub fn new(start: Point, end: Point) -> Self {
+		Self { start, end }
+	}
+
+	/// the horizontal extent, negative if the line is drawn right to left
+	pub fn dx(&self) -> i64 {
+		self.end.x - self.start.x
+	}
+
+	/// the vertical extent, negative if the line is drawn downwards
+	pub fn dy(&self) -> i64 {
+		self.end.y - self.start.y
+	}
+}
+
+#[cfg(feature = "geo")]
+impl From<Point> for geo::Point<i64> {
+	fn from(point: Point) -> Self {
+		geo::Point::new(point.x, point.y)
+	}
+}
+
+#[cfg(feature = "geo")]
+impl From<geo::Point<i64>> for Point {
+	fn from(point: geo::Point<i64>) -> Self {
+		Self::new(point.x(), point.y())
+	}
+}
+
+#[cfg(feature = "geo")]
+impl From<Line> for geo::Line<i64> {
+	fn from(line: Line) -> Self {
+		geo::Line::new(geo::Point::from(line.start), geo::Point::from(line.end))
+	}
+}
+
+#[cfg(feature = "geo")]
+impl From<geo::Line<i64>> for Line {
+	fn from(line: geo::Line<i64>) -> Self {
+		Self::new(Point::new(line.start.x, line.start.y), Point::new(line.end.x, line.end.y))
+	}
+}
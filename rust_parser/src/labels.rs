@@ -0,0 +1,59 @@
+/// the words exporters put around the extracted texts, German by default.
+/// start from `german` or `english` and change the fields for other languages
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct Labels {
+	pub date: String,
+	pub class: String,
+	/// followed by the number of the block, like "Block 3"
+	pub block: String,
+	pub substitution: String,
+	/// what a cancelled lesson is called in the plan
+	pub cancelled: String,
+	/// shown instead of an empty schedule
+	pub no_substitutions: String,
+}
+
+impl Default for Labels {
+	fn default() -> Self {
+		Self::german()
+	}
+}
+
+impl Labels {
+	pub fn german() -> Self {
+		Self {
+			date: "Datum".to_owned(),
+			class: "Klasse".to_owned(),
+			block: "Block".to_owned(),
+			substitution: "Vertretung".to_owned(),
+			cancelled: "Entfall".to_owned(),
+			no_substitutions: "Keine Vertretungen".to_owned(),
+		}
+	}
+
+	pub fn english() -> Self {
+		Self {
+			date: "Date".to_owned(),
+			class: "Class".to_owned(),
+			block: "Period".to_owned(),
+			substitution: "Substitution".to_owned(),
+			cancelled: "Cancelled".to_owned(),
+			no_substitutions: "No substitutions".to_owned(),
+		}
+	}
+
+	/// the built in labels for a language tag like "de" or "en-GB"
+	pub fn for_language(tag: &str) -> Option<Self> {
+		match tag.split(['-', '_']).next()?.to_ascii_lowercase().as_str() {
+			"de" => Some(Self::german()),
+			"en" => Some(Self::english()),
+			_ => None,
+		}
+	}
+
+	/// like "Block 3", `block` counting from 1
+	pub fn block_number(&self, block: usize) -> String {
+		format!("{} {}", self.block, block)
+	}
+}
@@ -1,47 +1,236 @@
+#[cfg(feature = "schedule")]
+use std::collections::hash_map::Entry;
+use std::cmp::Reverse;
 use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+use geometry::within;
+use marker::MarkerPairs;
+use recycle::Recycled;
 use std::ffi::OsStr;
 use std::path::Path;
-use lopdf::{Document, Stream};
+use lopdf::{Document, Object, ObjectId, Stream};
+use lopdf::content::{Content, Operation};
 use std::error::Error;
 use std::fs::OpenOptions;
 use std::io::Read;
 use std::iter::FilterMap;
 use std::slice::Iter;
-use geo::{Line, Point};
+use regex::Regex;
+#[cfg(feature = "schedule")]
 use substitution_common::{SubstitutionColumn, SubstitutionPDFExtractor, SubstitutionSchedule};
 
+mod analysis;
+#[cfg(feature = "schedule")]
+mod batch;
+#[cfg(feature = "bundle")]
+mod bundle;
+#[cfg(feature = "dates")]
+mod calendar;
+mod cancellation;
+mod cells;
+#[cfg(feature = "schedule")]
+mod columns;
+mod config;
+mod context;
+#[cfg(feature = "schedule")]
+mod convert;
+#[cfg(feature = "dates")]
+mod date;
+#[cfg(feature = "dates")]
+mod date_extractor;
+mod decoder;
+#[cfg(feature = "schedule")]
+mod diff;
+mod drift;
+mod dump;
+#[cfg(feature = "serde")]
+mod envelope;
+mod error;
+#[cfg(feature = "schedule")]
+mod export;
+pub mod geometry;
+mod labels;
+#[cfg(feature = "schedule")]
+mod mapping;
+mod marker;
+#[cfg(feature = "schedule")]
+mod merge;
+mod metadata;
+mod model;
+#[cfg(feature = "mqtt")]
+mod mqtt;
+mod overlay;
+#[cfg(feature = "parquet")]
+mod parquet_export;
+#[cfg(feature = "schedule")]
+mod pipeline;
+mod plain_text;
+mod postprocess;
+#[cfg(feature = "profile")]
+mod profile;
+mod progress;
+#[cfg(feature = "provenance")]
+mod provenance;
+mod recycle;
+mod redact;
+mod section;
+#[cfg(feature = "schedule")]
+mod signage;
+#[cfg(feature = "dates")]
+mod split;
+#[cfg(feature = "schedule")]
+mod store;
+mod strategy;
+mod synthetic;
+mod validation;
+#[cfg(feature = "schedule")]
+mod week;
+#[cfg(feature = "tabula")]
+mod verify;
+
+pub mod prelude;
+
+pub use analysis::{ColumnReport, PageReport, PageTiming, RowReport, StructureReport, TableReport};
+#[cfg(feature = "schedule")]
+pub use batch::{extract_batch, extract_directory, BatchProgress, BatchReport};
+#[cfg(feature = "bundle")]
+pub use bundle::write_bundle;
+#[cfg(feature = "dates")]
+pub use calendar::SchoolCalendar;
+pub use cancellation::CancellationToken;
+pub use cells::{CellKind, CellRef};
+#[cfg(feature = "schedule")]
+pub use columns::{ClassStatus, ScheduleReport};
+pub use config::ExtractorConfig;
+pub use context::ExtractionContext;
+#[cfg(feature = "dates")]
+pub use date::{Date, DateError, DateFilter};
+#[cfg(feature = "dates")]
+pub use date_extractor::{CreationDate, DateExtractor, MarkerDate, RegexDate};
+pub use decoder::{FnDecoder, FontEncodingDecoder, TextDecoder, WinAnsiDecoder};
+#[cfg(feature = "schedule")]
+pub use diff::{diff_schedules, CellDifference, ChangeClassifier, ChangeKind, ClassifiedDifference, Severity};
+pub use drift::{Deviation, DriftTolerance, Fingerprint, PageFingerprint, TableFingerprint};
+pub use dump::dump_operations;
+#[cfg(feature = "serde")]
+pub use envelope::JsonEnvelope;
+pub use error::ExtractionError;
+#[cfg(feature = "schedule")]
+pub use export::{write_long_csv, write_markdown};
+pub use geometry::{BoundingBox, DetectionTolerance, Line, Origin, Point};
+pub use labels::Labels;
+#[cfg(feature = "schedule")]
+pub use mapping::{CellTransform, DuplicateClasses, RowMapper};
+pub use marker::{BottomMarker, TopBoundary};
+#[cfg(feature = "schedule")]
+pub use merge::SubstitutionScheduleMerge;
+pub use metadata::DocumentMetadata;
+pub use model::{BlockEntry, Cell, Column, Confidence, ConfidenceFlag, RangedCell, RowLabel, Table};
+#[cfg(feature = "mqtt")]
+pub use mqtt::{MqttPublisher, MqttQos, MqttSettings};
+pub use overlay::escape;
+#[cfg(feature = "parquet")]
+pub use parquet_export::write_long_parquet;
+#[cfg(feature = "schedule")]
+pub use pipeline::Extraction;
+pub use postprocess::PostProcessRule;
+#[cfg(feature = "profile")]
+pub use profile::{BottomMarkerSetting, ExtractionProfile};
+pub use progress::{NoProgress, ProgressObserver};
+#[cfg(feature = "provenance")]
+pub use provenance::Source;
+pub use redact::redact;
+pub use section::{Section, SectionHeadings};
+#[cfg(feature = "schedule")]
+pub use signage::{write_signage_html, SignageSettings};
+#[cfg(feature = "dates")]
+pub use split::split_by_date;
+#[cfg(feature = "schedule")]
+pub use store::{ScheduleStore, StoreEvent};
+pub use strategy::{MarkerStrategy, TableDetectionStrategy};
+#[cfg(feature = "schedule")]
+pub use strategy::StrategyExtractor;
+pub use synthetic::SyntheticPlan;
+pub use validation::{GeometryReport, GeometryTolerance, GeometryViolation};
+#[cfg(feature = "schedule")]
+pub use week::WeekSchedule;
+#[cfg(feature = "tabula")]
+pub use verify::{verify, VerifyReport};
+
+#[cfg(feature = "schedule")]
+pub use substitution_common;
+#[cfg(feature = "tabula")]
+pub use tabula_pdf_parser;
 
 /// the parser itself
-pub struct HbsTableExtractor(Vec<PageObjects>);
+pub struct HbsTableExtractor {
+	pages: Vec<PageObjects>,
+	/// from the media box of every page
+	page_heights: Vec<i64>,
+	/// how long decoding the content streams of every page took
+	decode_times: Vec<Duration>,
+	metadata: DocumentMetadata,
+	config: ExtractorConfig,
+}
 
-/// all objects on a page
+/// all objects on a page, and the chords of the curves that were too curved to count as lines
 #[derive(Clone)]
-struct PageObjects(Vec<TableObject>);
+pub struct PageObjects(Vec<TableObject>, Vec<Line>);
 
 /// the text in the pdf
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
-struct Text {
-	text: String,
-	position: Point<i64>,
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct Text {
+	pub text: String,
+	pub position: Point,
+	/// size set by the last Tf, headers are usually set larger than the cells
+	pub font_size: f64,
+	/// horizontal scaling set by the last Tz in percent, 100 if unscaled
+	pub horizontal_scaling: f64,
+	/// the operation that drew the text, not part of equality so the same text drawn twice is still one text
+	#[cfg(feature = "provenance")]
+	pub source: Source,
+}
+
+impl PartialEq for Text {
+	fn eq(&self, other: &Self) -> bool {
+		self.text == other.text
+			&& self.position == other.position
+			&& self.font_size == other.font_size
+			&& self.horizontal_scaling == other.horizontal_scaling
+	}
+}
+
+// the sizes come straight from the content stream and are never NaN
+impl Eq for Text {}
+
+impl Hash for Text {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.text.hash(state);
+		self.position.hash(state);
+		self.font_size.to_bits().hash(state);
+		self.horizontal_scaling.to_bits().hash(state);
+	}
 }
 
 impl Text {
 	// only works left to right
 	fn between_x(&self, limit_start: i64, limit_end: i64) -> bool {
-		self.position.x() > limit_start && self.position.x() < limit_end
+		within(self.position.x(), limit_start, limit_end, 0)
 	}
 }
 
 /// all relevant pdf objects
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 enum TableObject {
-	Line(Line<i64>),
+	Line(Line),
 	Text(Text),
 }
 
 impl TableObject {
 	fn between_y(&self, limit_top: i64, limit_bottom: i64) -> bool {
-		let between = |o| o < limit_top && o > limit_bottom;
+		let between = |o| within(o, limit_bottom, limit_top, 0);
 
 		match self {
 			Self::Text(t) => {
@@ -57,7 +246,7 @@ impl TableObject {
 	fn intersects_x_border(&self, border: i64) -> bool {
 		match self {
 			Self::Line(l) => if l.dy() == 0 {
-				border >= l.start.x && border <= l.end.x
+				within(border, l.start.x, l.end.x, 1)
 			} else { false },
 			Self::Text(t) => t.position.y() == border,
 		}
@@ -77,39 +266,156 @@ impl HbsTableExtractor {
 	}
 
 	pub fn load_from<R: Read>(src: R) -> Result<Self, Box<dyn Error>> {
-		let document = Document::load_from(src)?;
+		Self::load_from_with_config(src, ExtractorConfig::default())
+	}
 
-		let mut pages = Vec::new();
+	pub fn load_from_with_config<R: Read>(src: R, config: ExtractorConfig) -> Result<Self, Box<dyn Error>> {
+		Self::from_document_with_config(&Document::load_from(src)?, config)
+	}
+
+	/// parses the pdf straight from a memory map of the file instead of reading it into a buffer first,
+	/// falls back to reading it if the file can't be mapped
+	#[cfg(feature = "mmap")]
+	pub fn load_mmap<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+		Self::load_mmap_with_config(path, ExtractorConfig::default())
+	}
+
+	#[cfg(feature = "mmap")]
+	pub fn load_mmap_with_config<P: AsRef<Path>>(path: P, config: ExtractorConfig) -> Result<Self, Box<dyn Error>> {
+		let file = OpenOptions::new().read(true).open(path)?;
+
+		// SAFETY: the map is only read while the document is parsed, the extractor keeps no reference to it.
+		// truncating the file from another process during that time is undefined behaviour, like for every map
+		match unsafe { memmap2::Mmap::map(&file) } {
+			Ok(map) => Self::from_document_with_config(&Document::load_mem(&map)?, config),
+			Err(_) => Self::load_from_with_config(file, config),
+		}
+	}
+
+	/// for documents that are already in memory, nothing refers to the document after this returns
+	pub fn from_document(document: Document) -> Result<Self, Box<dyn Error>> {
+		Self::from_document_ref(&document)
+	}
+
+	pub fn from_document_ref(document: &Document) -> Result<Self, Box<dyn Error>> {
+		Self::from_document_with_config(document, ExtractorConfig::default())
+	}
+
+	pub fn from_document_with_config(document: &Document, config: ExtractorConfig) -> Result<Self, Box<dyn Error>> {
+		Self::from_document_recycled(document, config, &mut Recycled::default())
+	}
+
+	/// `from_document_with_config` filling the buffers left in `recycled` instead of allocating new ones
+	pub(crate) fn from_document_recycled(document: &Document, config: ExtractorConfig, recycled: &mut Recycled) -> Result<Self, Box<dyn Error>> {
+		let page_count = document.get_pages().len();
+
+		let mut spare = std::mem::take(&mut recycled.pages);
+		let mut pages = Vec::with_capacity(page_count);
+		let mut page_heights = std::mem::take(&mut recycled.page_heights);
+		let mut decode_times = std::mem::take(&mut recycled.decode_times);
+		page_heights.clear();
+		decode_times.clear();
+
+		for (idx, page) in document.page_iter().enumerate() {
+			config.cancellation.check()?;
+
+			let started = Instant::now();
+
+			page_heights.push(page_height(document, page)?);
+
+			// the named encoding of every font resource of the page
+			let fonts = document.get_page_fonts(page)
+				.into_iter()
+				.filter_map(|(name, font)| Some((name, font.get(b"Encoding").ok()?.as_name_str().ok()?.to_owned())))
+				.collect::<HashMap<Vec<u8>, String>>();
+
+			// a page can be split into multiple content streams, they all make up one page
+			let mut objects = match spare.pop() {
+				Some(mut objects) => {
+					objects.0.clear();
+					objects.1.clear();
+					objects
+				}
+				None => PageObjects(Vec::new(), Vec::new()),
+			};
+
+			// operations are counted across the streams, like `dump_operations` lists them
+			let mut operation_count = 0;
 
-		for page in document.page_iter() {
 			for object_id in document.get_page_contents(page) {
 				let object = document.get_object(object_id)?;
 
 				if let Ok(stream) = object.as_stream() {
-					pages.push(PageObjects::from_stream(stream)?);
+					operation_count += objects.extend_from_stream(stream, idx, operation_count, &fonts, &config, &mut recycled.unique)?;
 				};
 			};
+
+			objects.merge_segments(config.tolerance.segment_gap);
+
+			pages.push(objects);
+			decode_times.push(started.elapsed());
+			config.observer.page_loaded(idx, page_count);
 		};
 
-		Ok(Self(pages))
+		let extractor = Self { pages, page_heights, decode_times, metadata: DocumentMetadata::read(document), config };
+
+		if extractor.config.require_content && extractor.is_empty() {
+			return Err(ExtractionError::NoContent.into());
+		}
+
+		Ok(extractor)
+	}
+
+	/// the same pages under a different config, without decoding the content streams again.
+	/// fails if `config` changes a setting that is applied while loading, the curve flatness or segment gap
+	pub fn reconfigured(&self, config: ExtractorConfig) -> Result<Self, Box<dyn Error>> {
+		if config.tolerance.curve_flatness != self.config.tolerance.curve_flatness
+			|| config.tolerance.segment_gap != self.config.tolerance.segment_gap {
+			return Err("curve flatness and segment gap only apply while loading, load the pdf again to change them".into());
+		}
+
+		Ok(Self {
+			pages: self.pages.clone(),
+			page_heights: self.page_heights.clone(),
+			decode_times: self.decode_times.clone(),
+			metadata: self.metadata.clone(),
+			config,
+		})
 	}
 
+	#[cfg(feature = "dates")]
 	pub fn extract_date(&self) -> Result<i64, Box<dyn Error>> {
-		let date_string = self.0.iter()
-			.map(|p| p.texts())
-			.flatten()
-			.find(|t| t.text.contains("Datum: "))
-			.ok_or("Couldn't find the date string in PDF")?
-			.text
-			.as_str();
+		Ok(self.issue_date()?.timestamp_millis())
+	}
 
-		let date_begin = date_string.rfind(' ').ok_or("Date string malformed")? + 1;
+	/// the date of the first configured `DateExtractor` that finds one
+	#[cfg(feature = "dates")]
+	pub(crate) fn issue_date(&self) -> Result<Date, Box<dyn Error>> {
+		let mut error = None;
 
-		Ok(
-			chrono::NaiveDate::parse_from_str(&date_string[date_begin..], "%d.%m.%Y")?
-				.and_hms_milli(0, 0, 0, 0)
-				.timestamp_millis()
-		)
+		for date_extractor in &self.config.date_extractors {
+			match date_extractor.extract(self) {
+				Ok(date) => return Ok(date),
+				Err(e) => error = Some(e),
+			}
+		}
+
+		Err(error.unwrap_or_else(|| "no date extractor configured".into()))
+	}
+
+	/// `issue_date`, failing with `ExtractionError::FilteredDate` if the date filter rejects it
+	#[cfg(feature = "schedule")]
+	pub(crate) fn filtered_date(&self) -> Result<Date, Box<dyn Error>> {
+		let date = self.issue_date()?;
+
+		match &self.config.date_filter {
+			Some(filter) if !filter.keeps(date) => Err(ExtractionError::FilteredDate {
+				year: date.year(),
+				month: date.month(),
+				day: date.day(),
+			}.into()),
+			_ => Ok(date),
+		}
 	}
 
 	// flattens by the first two vectors and joins the most inner one with '\n'
@@ -128,144 +434,602 @@ impl HbsTableExtractor {
 			.collect())
 	}
 
-	pub fn extract_tables(&mut self) -> Result<Vec<Page>, Box<dyn Error>> {
-		Ok(self.0.iter()
-			.map(|p| p.extract_table_objects())
-			.collect::<Result<Vec<Vec<TableObjects>>, Box<dyn Error>>>()?
-			.iter()
-			.map(|tc| tc.iter().map(|t| t.extract_columns()))
-			.map(|c|
-				c.map(|mut tt| tt.drain(..)
-					.map(|mut t|
-						t.generate_column()
-					).collect::<Result<Vec<Vec<Vec<String>>>, Box<dyn Error>>>()
-				).collect::<Result<Vec<Vec<Vec<Vec<String>>>>, Box<dyn Error>>>()
-			).collect::<Result<Vec<Page>, Box<dyn Error>>>()?)
+	/// the raw tables of every page, pages without a table stay empty as long as another page has one.
+	/// fails with `ExtractionError::NoTablesDetected` if no page has a table with at least one column
+	pub fn extract_tables(&mut self) -> Result<Vec<RawPage>, Box<dyn Error>> {
+		let pages = self.raw_tables()?;
+
+		if pages.iter().flatten().all(|table| table.is_empty()) {
+			return Err(ExtractionError::NoTablesDetected {
+				pages_scanned: pages.len(),
+				// every table area starts at a marker, even if no column was found in it
+				markers_found: pages.iter().map(Vec::len).sum(),
+			}.into());
+		}
+
+		Ok(pages)
 	}
-}
 
-type Page = Vec<Table>;
-type Table = Vec<Column>;
-type Column = Vec<CellContent>;
-type CellContent = Vec<String>;
+	/// the substitution schedule of the document, mapped with the configured `RowMapper`.
+	/// fails with `ExtractionError::UnpairedMarker` if a table has no end, `schedule_with_status` reports it instead
+	#[cfg(feature = "schedule")]
+	pub fn schedule(&mut self) -> Result<SubstitutionSchedule, Box<dyn Error>> {
+		// checked first, a rejected day is not worth reading the tables of
+		let date = self.filtered_date()?;
 
-impl PageObjects {
-	fn from_stream(stream: &Stream) -> Result<Self, Box<dyn std::error::Error>> {
-		let mut stream = stream.to_owned();
-		stream.decompress();
-		let stream = stream.decode_content()?;
+		if let Some(unpaired) = self.unpaired_tables().next() {
+			return Err(unpaired.into());
+		}
 
-		let mut objects = HashSet::new();
+		schedule_from_raw_tables(&self.raw_tables()?, date.timestamp_millis(), &self.config)
+	}
 
+	/// an `ExtractionError::UnpairedMarker` for every top marker without a bottom marker, their tables are left out
+	pub(crate) fn unpaired_tables(&self) -> impl Iterator<Item = ExtractionError> + '_ {
+		self.pages.iter().enumerate().flat_map(move |(page, objects)| {
+			objects.marker_pairs(&self.config).unpaired_tops.into_iter().map(move |y| ExtractionError::UnpairedMarker {
+				page,
+				y: self.config.origin.y(y, self.page_heights[page]),
+			})
+		})
+	}
 
-		//find all Tj's and their position through the previous Td's and put them as a Text struct in an array
-		//find all l's and their position through the previous m's and put them as a Line struct in an array
-		for (i, op) in stream.operations.iter().enumerate() {
-			match op.operator.as_str() {
-				"Tj" => {
-					let td = &stream.operations[i - 1];
+	pub fn page_count(&self) -> usize {
+		self.pages.len()
+	}
 
-					if td.operator == "Td" {
-						let td_ops = &td.operands;
-						let tj_ops = &op.operands;
+	/// no page has any text or line the extractor understands
+	pub fn is_empty(&self) -> bool {
+		self.pages.iter().all(|p| p.0.is_empty())
+	}
 
-						let text = Document::decode_text(
-							Some("WinAnsiEncoding"),
-							tj_ops[0].as_str()?
-						);
+	/// every text matching `pattern` with the index of its page, in no particular order within a page
+	pub fn find_text(&self, pattern: &Regex) -> Vec<(usize, Text)> {
+		self.pages.iter()
+			.enumerate()
+			.flat_map(|(idx, page)| page.texts()
+				.filter(|t| pattern.is_match(&t.text))
+				.map(move |t| (idx, t.clone())))
+			.collect()
+	}
 
-						let position = Point::new(
-							td_ops[0].as_f64()? as i64,
-							td_ops[1].as_f64()? as i64,
-						);
+	/// title, producer and so on of the document
+	pub fn metadata(&self) -> &DocumentMetadata {
+		&self.metadata
+	}
 
-						objects.insert(TableObject::Text(Text {
-							text,
-							position
-						}));
-					} else {
-						return Err("While parsing pdf: Td expected before Tj".into());
-					}
+	/// height of the media box of a page, `None` if there is no such page
+	pub fn page_height(&self, page: usize) -> Option<i64> {
+		self.page_heights.get(page).copied()
+	}
+
+	/// `bbox` of a page in the configured origin
+	pub(crate) fn exposed(&self, page: usize, bbox: BoundingBox) -> BoundingBox {
+		bbox.with_origin(self.config.origin, self.page_heights[page])
+	}
+
+	/// the decoded objects of every page, for strategies that detect tables themselves
+	/// positions are as they are in the pdf, relative to the bottom left whatever the configured origin
+	pub fn pages(&self) -> &[PageObjects] {
+		&self.pages
+	}
+
+	fn raw_tables(&self) -> Result<Vec<RawPage>, Box<dyn Error>> {
+		let observer = &self.config.observer;
+		let cancellation = &self.config.cancellation;
+		let mut pages = Vec::new();
+		let mut scratch = Vec::new();
+
+		for (page_idx, page) in self.pages.iter().enumerate() {
+			cancellation.check()?;
+
+			let tables = page.extract_table_objects(&self.config)?;
+			observer.tables_detected(page_idx, tables.len());
+
+			let mut extracted_tables = Vec::new();
+
+			for (table_idx, table) in tables.iter().enumerate() {
+				let mut columns = Vec::new();
+
+				for mut column in table.extract_columns() {
+					cancellation.check()?;
+
+					let mut column = column.generate_column(&mut scratch)?;
+					postprocess::post_process(&self.config.post_processing, &self.config.placeholders, &mut column);
+					columns.push(column);
 				}
-				"l" => {
-					let m = &stream.operations[i - 1];
 
-					if m.operator == "m" {
-						let m_ops = &m.operands;
-						let l_ops = &op.operands;
+				observer.columns_assembled(page_idx, table_idx, columns.len());
+				extracted_tables.push(columns);
+			}
+
+			pages.push(extracted_tables);
+		}
+
+		Ok(pages)
+	}
+}
+
+/// text state of a content stream, only the line start is reset by BT
+/// the extractor does not follow the text position while a line is being shown, every string gets its line start
+struct TextState {
+	line: (f64, f64),
+	leading: f64,
+	font_size: f64,
+	horizontal_scaling: f64,
+	/// the operation being walked
+	#[cfg(feature = "provenance")]
+	source: Source,
+}
+
+impl Default for TextState {
+	fn default() -> Self {
+		Self {
+			line: (0.0, 0.0),
+			leading: 0.0,
+			font_size: 0.0,
+			horizontal_scaling: 100.0,
+			#[cfg(feature = "provenance")]
+			source: Source::default(),
+		}
+	}
+}
+
+impl TextState {
+	/// T*, also implied by ' and "
+	fn next_line(&mut self) {
+		self.line.1 -= self.leading;
+	}
+
+	fn show(&self, text: String) -> TableObject {
+		TableObject::Text(Text {
+			text,
+			position: Point::new(self.line.0 as i64, self.line.1 as i64),
+			font_size: self.font_size,
+			horizontal_scaling: self.horizontal_scaling,
+			#[cfg(feature = "provenance")]
+			source: self.source,
+		})
+	}
+}
+
+/// the "Block" headers, a text only counts if it is exactly that word and nothing on its row is further left,
+/// so a class that happens to be called "Block" can't start a table
+fn block_markers<'a, I: Iterator<Item = &'a Text>>(texts: I, tolerance: &DetectionTolerance) -> Vec<&'a Text> {
+	let texts = texts.collect::<Vec<&Text>>();
+
+	texts.iter()
+		.filter(|t| t.text.trim() == "Block")
+		.filter(|t| !texts.iter().any(|other| {
+			within(other.position.y(), t.position.y(), t.position.y(), tolerance.header_baseline) && other.position.x() < t.position.x()
+		}))
+		.copied()
+		.collect()
+}
+
+/// height of the media box, which may be inherited from the page tree, A4 if there is none
+fn page_height(document: &Document, page: ObjectId) -> Result<i64, Box<dyn Error>> {
+	let mut node = document.get_dictionary(page)?;
+
+	loop {
+		if let Ok(media_box) = node.get(b"MediaBox").and_then(|b| document.dereference(b)).and_then(|(_, b)| b.as_array()) {
+			if media_box.len() != 4 {
+				return Err("MediaBox does not have 4 entries".into());
+			}
+
+			return Ok((number(&media_box[3])? - number(&media_box[1])?) as i64);
+		}
+
+		match node.get(b"Parent").and_then(Object::as_reference) {
+			Ok(parent) => node = document.get_dictionary(parent)?,
+			Err(_) => return Ok(842),
+		}
+	}
+}
+
+/// numeric operand, generators write integers wherever the value happens to be whole
+fn number(operand: &Object) -> Result<f64, lopdf::Error> {
+	match *operand {
+		Object::Integer(value) => Ok(value as f64),
+		_ => operand.as_f64(),
+	}
+}
 
+/// collects the objects of one content stream
+struct StreamWalker<'a> {
+	fonts: &'a HashMap<Vec<u8>, String>,
+	decoder: &'a dyn TextDecoder,
+	encoding: Option<&'a str>,
+	state: TextState,
+	/// the end of the last path segment
+	current_point: Option<(f64, f64)>,
+	/// how far the control points of a curve may be from the line between its ends for it to count as that line
+	curve_flatness: i64,
+	objects: HashSet<TableObject>,
+	/// chords of the curves that were not flat enough
+	curves: Vec<Line>,
+	/// the path under construction, it only counts once it is painted
+	path: Vec<PathSegment>,
+	/// box around all points of the path under construction
+	path_bounds: Option<BoundingBox>,
+	/// W or W* was given for the path under construction
+	clips: bool,
+	/// box around the clipping path, objects entirely outside of it are invisible. `None` if nothing is clipped
+	clip: Option<BoundingBox>,
+	/// the clips saved by q
+	clip_stack: Vec<Option<BoundingBox>>,
+}
+
+enum PathSegment {
+	/// a line or a nearly straight curve
+	Line(Line),
+	/// the chord of a curve that is not flat enough
+	Curve(Line),
+}
+
+impl StreamWalker<'_> {
+	//find all Tj's and their position through the text line state and put them as a Text struct in an array
+	//find all l's and their position through the previous m's and put them as a Line struct in an array
+	//curves that are nearly straight count as lines from their start to their end
+	//lines only count once their path is stroked or filled, not if it ends with n like the paths of clipping regions
+	//objects outside of the box around the clipping path are left out
+	fn apply(&mut self, operations: &[Operation], i: usize) -> Result<(), Box<dyn Error>> {
+		let op = &operations[i];
+		let state = &mut self.state;
+
+		match op.operator.as_str() {
+			"Tf" => {
+				self.encoding = op.operands.first()
+					.and_then(|name| name.as_name().ok())
+					.and_then(|name| self.fonts.get(name))
+					.map(String::as_str);
+				state.font_size = op.operands.get(1).map_or(Ok(0.0), number)?;
+			}
+			"Tz" => state.horizontal_scaling = number(operand(op, 0)?)?,
+			"BT" => state.line = (0.0, 0.0),
+			"Td" => {
+				state.line.0 += number(operand(op, 0)?)?;
+				state.line.1 += number(operand(op, 1)?)?;
+			}
+			"TD" => {
+				state.leading = -number(operand(op, 1)?)?;
+				state.line.0 += number(operand(op, 0)?)?;
+				state.line.1 += number(operand(op, 1)?)?;
+			}
+			"Tm" => state.line = (number(operand(op, 4)?)?, number(operand(op, 5)?)?),
+			"TL" => state.leading = number(operand(op, 0)?)?,
+			"T*" => state.next_line(),
+			// ' shows a string on the next line, " additionally sets word and char spacing first
+			"'" | "\"" => {
+				let string = op.operands.last().ok_or("string expected for ' or \"")?;
+
+				state.next_line();
+				let text = state.show(decoder::decode_shown(self.decoder, self.encoding, string.as_str()?));
+				self.insert(text);
+			}
+			"Tj" => {
+				let text = state.show(decoder::decode_shown(self.decoder, self.encoding, operand(op, 0)?.as_str()?));
+				self.insert(text);
+			}
+			"l" => {
+				match i.checked_sub(1).map(|prev| &operations[prev]) {
+					Some(m) if m.operator == "m" => {
 						let start = Point::new(
-							m_ops[0].as_f64()? as i64,
-							m_ops[1].as_f64()? as i64,
+							number(operand(m, 0)?)? as i64,
+							number(operand(m, 1)?)? as i64,
 						);
 
 						let end = Point::new(
-							l_ops[0].as_f64()? as i64,
-							l_ops[1].as_f64()? as i64,
+							number(operand(op, 0)?)? as i64,
+							number(operand(op, 1)?)? as i64,
 						);
 
-						objects.insert(TableObject::Line(Line::new(start, end)));
-					} else {
-						return Err("While parsing pdf: m expected before l".into());
+						self.path.push(PathSegment::Line(Line::new(start, end)));
+						self.current_point = Some((number(operand(op, 0)?)?, number(operand(op, 1)?)?));
+						self.extend_path(&[(start.x as f64, start.y as f64), (end.x as f64, end.y as f64)]);
 					}
+					_ => return Err("m expected before l".into()),
 				}
-				_ => (),
 			}
+			"m" => {
+				let point = (number(operand(op, 0)?)?, number(operand(op, 1)?)?);
+
+				self.current_point = Some(point);
+				self.extend_path(&[point]);
+			}
+			"re" => {
+				let (x, y) = (number(operand(op, 0)?)?, number(operand(op, 1)?)?);
+				let (width, height) = (number(operand(op, 2)?)?, number(operand(op, 3)?)?);
+
+				self.current_point = Some((x, y));
+				self.extend_path(&[(x, y), (x + width, y + height)]);
+			}
+			// c has both control points, v starts with the current point as the first one, y ends with the end point
+			"c" | "v" | "y" => {
+				let start = self.current_point.ok_or_else(|| format!("m expected before {}", op.operator))?;
+
+				let values = op.operands.iter()
+					.map(number)
+					.collect::<Result<Vec<f64>, _>>()?;
+
+				let (controls, end) = match (op.operator.as_str(), values.as_slice()) {
+					("c", [x1, y1, x2, y2, x3, y3]) => ([(*x1, *y1), (*x2, *y2)], (*x3, *y3)),
+					("v", [x2, y2, x3, y3]) => ([start, (*x2, *y2)], (*x3, *y3)),
+					("y", [x1, y1, x3, y3]) => ([(*x1, *y1), (*x3, *y3)], (*x3, *y3)),
+					_ => return Err(format!("{} operands for {}", values.len(), op.operator).into()),
+				};
+
+				let chord = Line::new(
+					Point::new(start.0 as i64, start.1 as i64),
+					Point::new(end.0 as i64, end.1 as i64),
+				);
+
+				if controls.iter().all(|control| distance_to_chord(*control, start, end) <= self.curve_flatness as f64) {
+					self.path.push(PathSegment::Line(chord));
+				} else {
+					self.path.push(PathSegment::Curve(chord));
+				}
+
+				self.current_point = Some(end);
+				self.extend_path(&[start, controls[0], controls[1], end]);
+			}
+			"S" | "s" | "f" | "F" | "f*" | "B" | "B*" | "b" | "b*" => {
+				for segment in std::mem::take(&mut self.path) {
+					match segment {
+						PathSegment::Line(line) => self.insert(TableObject::Line(line)),
+						PathSegment::Curve(chord) => if self.visible(&line_bounds(&chord)) {
+							self.curves.push(chord);
+						},
+					}
+				}
+
+				self.end_path();
+			}
+			"n" => {
+				self.path.clear();
+				self.end_path();
+			}
+			"W" | "W*" => self.clips = true,
+			"q" => self.clip_stack.push(self.clip),
+			"Q" => if let Some(clip) = self.clip_stack.pop() {
+				self.clip = clip;
+			},
+			_ => (),
 		}
 
-		Ok(Self(objects.drain().collect()))
+		Ok(())
 	}
+}
 
-	fn extract_table_objects(&self) -> Result<Vec<TableObjects>, Box<dyn Error>> {
-		let mut top_limits = self.texts()
-			.filter(|t| t.text == "Block")
-			.map(|t| t.position.y() + 4 /* add a tolerance of 4 */)
-			.collect::<Vec<i64>>();
+impl StreamWalker<'_> {
+	/// adds the object unless it is outside of the clip
+	fn insert(&mut self, object: TableObject) {
+		let bounds = match &object {
+			TableObject::Text(t) => BoundingBox { left: t.position.x, bottom: t.position.y, right: t.position.x, top: t.position.y },
+			TableObject::Line(l) => line_bounds(l),
+		};
 
-		top_limits.sort();
+		if self.visible(&bounds) {
+			self.objects.insert(object);
+		}
+	}
+
+	fn visible(&self, bounds: &BoundingBox) -> bool {
+		self.clip.is_none_or(|clip| clip.touches(bounds))
+	}
 
-		let mut bottom_limits = self.texts()
-			.filter(|t| t.text.contains("15:15"))
+	fn extend_path(&mut self, points: &[(f64, f64)]) {
+		let points = points.iter().map(|(x, y)| (*x as i64, *y as i64));
+		self.path_bounds = BoundingBox::around(self.path_bounds.iter().flat_map(|b| [(b.left, b.bottom), (b.right, b.top)]).chain(points));
+	}
+
+	/// the path is painted or dropped, a clip given for it applies from now on
+	fn end_path(&mut self) {
+		if std::mem::take(&mut self.clips) {
+			if let Some(bounds) = self.path_bounds {
+				self.clip = Some(self.clip.map_or(bounds, |clip| clip.intersection(&bounds)));
+			}
+		}
+
+		self.path_bounds = None;
+	}
+}
+
+fn line_bounds(line: &Line) -> BoundingBox {
+	BoundingBox {
+		left: line.start.x.min(line.end.x),
+		bottom: line.start.y.min(line.end.y),
+		right: line.start.x.max(line.end.x),
+		top: line.start.y.max(line.end.y),
+	}
+}
+
+/// distance of `point` from the line through `start` and `end`, or from `start` if they are the same
+fn distance_to_chord(point: (f64, f64), start: (f64, f64), end: (f64, f64)) -> f64 {
+	let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+	let length = dx.hypot(dy);
+
+	if length == 0.0 {
+		return (point.0 - start.0).hypot(point.1 - start.1);
+	}
+
+	(dx * (point.1 - start.1) - dy * (point.0 - start.0)).abs() / length
+}
+
+fn operand(op: &Operation, idx: usize) -> Result<&Object, String> {
+	op.operands.get(idx).ok_or_else(|| format!("operand {} of {} missing", idx, op.operator))
+}
+
+/// the operations around `idx` as they appear in the stream, for error messages
+fn operator_window(operations: &[Operation], idx: usize) -> String {
+	operations[idx.saturating_sub(3)..(idx + 4).min(operations.len())]
+		.iter()
+		.map(|op| dump::format_operation(op, false))
+		.collect::<Vec<String>>()
+		.join(" ")
+}
+
+pub type RawPage = Vec<RawTable>;
+pub type RawTable = Vec<RawColumn>;
+pub type RawColumn = Vec<RawCell>;
+pub type RawCell = Vec<String>;
+
+impl PageObjects {
+	/// adds the objects of one content stream of a page and returns its number of operations,
+	/// `first_operation` is the number of operations in the streams of the page before it.
+	/// the objects are deduplicated in `unique`, which is left empty for the next stream
+	fn extend_from_stream(&mut self, stream: &Stream, page: usize, first_operation: usize, fonts: &HashMap<Vec<u8>, String>, config: &ExtractorConfig, unique: &mut HashSet<TableObject>) -> Result<usize, Box<dyn std::error::Error>> {
+		// decoded straight from the shared bytes, only the decompressed copy is allocated
+		let stream = match stream.decompressed_content() {
+			Ok(plain) => Content::decode(&plain)?,
+			Err(_) => Content::decode(&stream.content)?,
+		};
+
+		let mut walker = StreamWalker {
+			fonts,
+			decoder: config.decoder.as_ref(),
+			encoding: None,
+			state: TextState {
+				#[cfg(feature = "provenance")]
+				source: Source { page, operation: first_operation },
+				..TextState::default()
+			},
+			current_point: None,
+			curve_flatness: config.tolerance.curve_flatness,
+			objects: std::mem::take(unique),
+			curves: Vec::new(),
+			path: Vec::new(),
+			path_bounds: None,
+			clips: false,
+			clip: None,
+			clip_stack: Vec::new(),
+		};
+
+		for i in 0..stream.operations.len() {
+			#[cfg(feature = "provenance")]
+			{
+				walker.state.source.operation = first_operation + i;
+			}
+
+			walker.apply(&stream.operations, i).map_err(|e| ExtractionError::ContentStream {
+				page,
+				operation: first_operation + i,
+				window: operator_window(&stream.operations, i),
+				message: e.to_string(),
+			})?;
+		}
+
+		self.0.extend(walker.objects.drain());
+		self.1.append(&mut walker.curves);
+		*unique = walker.objects;
+
+		Ok(stream.operations.len())
+	}
+
+	/// y of every "Block" header, sorted
+	fn top_markers(&self, tolerance: &DetectionTolerance) -> Vec<i64> {
+		let mut markers = block_markers(self.texts(), tolerance)
+			.into_iter()
 			.map(|t| t.position.y())
 			.collect::<Vec<i64>>();
 
-		bottom_limits.sort();
+		markers.sort();
+		markers
+	}
 
-		// Sanity check
-		if bottom_limits.len() != top_limits.len() {
-			return Err("bottom and top limits don't match up".into())
-		}
+	/// y of every time of the last block, sorted
+	fn bottom_markers(&self, marker: &BottomMarker) -> Vec<i64> {
+		let mut markers = match marker {
+			BottomMarker::Text(pattern) => self.texts()
+				.filter(|t| pattern.is_match(&t.text))
+				.map(|t| t.position.y())
+				.collect::<Vec<i64>>(),
+			BottomMarker::LowestLine => self.lines()
+				.filter(|l| l.dy() == 0)
+				.map(|l| l.start.y)
+				.min()
+				.into_iter()
+				.collect(),
+		};
 
-		// adjust bottom_limit to extend to the bottom line and add a tolerance
-		let mut line_deltas = Vec::new();
+		markers.sort();
+		markers
+	}
 
-		for limit in &bottom_limits {
-			line_deltas.push(self.lines().filter_map(|l| {
-				let delta = l.start.y - limit;
+	/// y of the highest long horizontal line between each bottom marker and the bottom line of the table above it
+	fn top_rulings(&self, bottom_markers: &[i64]) -> Vec<i64> {
+		let longest = self.lines()
+			.filter(|l| l.dy() == 0)
+			.map(|l| l.dx().abs())
+			.max()
+			.unwrap_or(0);
+
+		bottom_markers.iter()
+			.enumerate()
+			.filter_map(|(idx, bottom)| {
+				// the table above ends at the first line below its marker, everything from there up is its own
+				let ceiling = bottom_markers.get(idx + 1)
+					.and_then(|upper| self.lines().filter(|l| l.dy() == 0 && l.start.y < *upper).map(|l| l.start.y).max())
+					.unwrap_or(i64::MAX);
+
+				self.lines()
+					.filter(|l| l.dy() == 0 && l.dx().abs() * 2 >= longest)
+					.map(|l| l.start.y)
+					.filter(|y| y > bottom && *y < ceiling)
+					.max()
+			})
+			.collect()
+	}
 
-				if l.dy() == 0 && delta.is_negative() {
-					Some(delta)
-				} else {
-					None
-				}
-			}).max().ok_or("table bound could not be found")?)
+	/// the markers of the tables on the page, paired by the first detector that leaves no marker unpaired,
+	/// or else by the one finding the most tables
+	fn marker_pairs(&self, config: &ExtractorConfig) -> MarkerPairs {
+		let bottom_markers = self.bottom_markers(&config.bottom_marker);
+		let mut best: Option<MarkerPairs> = None;
+
+		for boundary in &config.top_boundaries {
+			let top_markers = match boundary {
+				TopBoundary::BlockHeader => self.top_markers(&config.tolerance),
+				TopBoundary::TopRuling => self.top_rulings(&bottom_markers),
+			};
+
+			let pairs = MarkerPairs::pair(&top_markers, &bottom_markers, config.tolerance.max_table_height);
+
+			if pairs.is_complete() {
+				return pairs;
+			}
+
+			if best.as_ref().is_none_or(|best| pairs.tables.len() > best.tables.len()) {
+				best = Some(pairs);
+			}
 		}
 
-		// // Sanity check
-		// if line_deltas.len() != bottom_limits.len() {
-		// 	return Err("something went wrong".into())
-		// }
+		best.unwrap_or_default()
+	}
 
-		let mut line_deltas = line_deltas.into_iter();
+	fn extract_table_objects(&self, config: &ExtractorConfig) -> Result<Vec<TableObjects>, Box<dyn Error>> {
+		let mut limits = Vec::new();
+
+		for (top, bottom) in self.marker_pairs(config).tables {
+			// extend the bottom limit to the line below the marker
+			let line_delta = match config.bottom_marker {
+				// the marker already is the bottom line
+				BottomMarker::LowestLine => 0,
+				BottomMarker::Text(_) => self.lines()
+					.filter(|l| l.dy() == 0)
+					.map(|l| l.start.y - bottom)
+					.filter(|delta| delta.is_negative())
+					.max()
+					.ok_or("table bound could not be found")?,
+			};
 
-		let bottom_limit_y = bottom_limits.drain(..)
-			.map(|l| line_deltas.next().map(|d| l + d - 4 /* add a tolerance of -4 */))
-			.collect::<Option<Vec<i64>>>().ok_or("line_deltas has a different length than bottom_limits")?;
+			limits.push((top + config.tolerance.table_margin, bottom + line_delta - config.tolerance.table_margin));
+		}
 
-		let mut extracted_tables = vec![TableObjects(Vec::new()); top_limits.len()];
+		let mut extracted_tables = vec![TableObjects(Vec::new(), config.tolerance); limits.len()];
 
 		for object in &self.0 {
-			for (idx, (top_bound, bottom_bound)) in top_limits.iter().zip(&bottom_limit_y).enumerate() {
+			for (idx, (top_bound, bottom_bound)) in limits.iter().enumerate() {
 				if object.between_y(*top_bound, *bottom_bound) {
 					extracted_tables[idx].0.push(object.clone());
 				}
@@ -275,39 +1039,113 @@ impl PageObjects {
 		Ok(extracted_tables)
 	}
 
-	fn lines<'a>(&'a self) -> FilterMap<Iter<'_, TableObject>, fn(&'a TableObject) -> Option<&'a Line<i64>>> {
+	/// all lines on the page, in no particular order
+	pub fn lines(&self) -> impl Iterator<Item = &Line> {
 		self.0.iter().filter_map(|o| if let TableObject::Line(l) = o {Some(l)} else {None})
 	}
 
-	fn texts<'a>(&'a self) -> FilterMap<Iter<'_, TableObject>, fn(&'a TableObject) -> Option<&'a Text>> {
+	/// all texts on the page, in no particular order
+	pub fn texts(&self) -> impl Iterator<Item = &Text> {
 		self.0.iter().filter_map(|o| if let TableObject::Text(t) = o {Some(t)} else {None})
 	}
+
+	/// joins horizontal lines at the same y, and vertical ones at the same x, that overlap, touch or are at most
+	/// `gap` apart into one line drawn left to right or bottom to top. generators like to draw a long rule as many
+	/// short pieces, which would otherwise be counted as separate rows. slanted lines are left alone
+	fn merge_segments(&mut self, gap: i64) {
+		// horizontal by y and vertical by x, as (start, end) along the line
+		let mut horizontal: HashMap<i64, Vec<(i64, i64)>> = HashMap::new();
+		let mut vertical: HashMap<i64, Vec<(i64, i64)>> = HashMap::new();
+
+		self.0.retain(|object| match object {
+			TableObject::Line(l) if l.dy() == 0 && l.dx() != 0 => {
+				horizontal.entry(l.start.y).or_default().push((l.start.x.min(l.end.x), l.start.x.max(l.end.x)));
+				false
+			}
+			TableObject::Line(l) if l.dx() == 0 && l.dy() != 0 => {
+				vertical.entry(l.start.x).or_default().push((l.start.y.min(l.end.y), l.start.y.max(l.end.y)));
+				false
+			}
+			_ => true,
+		});
+
+		for (y, segments) in horizontal {
+			for (start, end) in merge_ranges(segments, gap) {
+				self.0.push(TableObject::Line(Line::new(Point::new(start, y), Point::new(end, y))));
+			}
+		}
+
+		for (x, segments) in vertical {
+			for (start, end) in merge_ranges(segments, gap) {
+				self.0.push(TableObject::Line(Line::new(Point::new(x, start), Point::new(x, end))));
+			}
+		}
+	}
+}
+
+/// the ranges with all that overlap or are at most `gap` apart joined, in order
+fn merge_ranges(mut ranges: Vec<(i64, i64)>, gap: i64) -> Vec<(i64, i64)> {
+	ranges.sort_unstable();
+
+	let mut merged: Vec<(i64, i64)> = Vec::new();
+
+	for (start, end) in ranges {
+		match merged.last_mut() {
+			Some(last) if start <= last.1 + gap.max(0) => last.1 = last.1.max(end),
+			_ => merged.push((start, end)),
+		}
+	}
+
+	merged
 }
 
 #[derive(Clone)]
-struct TableObjects(Vec<TableObject>);
+struct TableObjects(Vec<TableObject>, DetectionTolerance);
 
 impl TableObjects {
 	fn extract_columns(&self) -> Vec<TableColumn> {
-		let header_height = self.texts()
-			.find(|t| t.text == "Block")
-			.expect("String 'Block' not found")
-			.position.y();
+		let marker = self.marker();
+
+		// without a "Block" header the highest texts of the table are the class headers
+		let header_height = match marker {
+			Some(marker) => marker.position.y(),
+			None => match self.texts().map(|t| t.position.y()).max() {
+				Some(y) => y,
+				None => return Vec::new(),
+			},
+		};
 
-		let mut columns = Vec::new();
+		let mut headers = Vec::new();
 
 		for header in self.texts() {
 			// TODO merge with between_y function
-			if header.position.y() < &header_height + 2 && /* 4 tolerance in total */
-				header.position.y() > &header_height - 2 {
-				if header.text != "Block" {
-					columns.push(
-						TableColumn {
-							header: header.to_owned(),
-							column: Vec::new(),
-						}
-					);
-				}
+			if within(header.position.y(), header_height, header_height, self.1.header_baseline) && Some(header) != marker {
+				headers.push(header);
+			}
+		}
+
+		headers.sort_by_key(|h| h.position.x());
+
+		let mut columns = Vec::new();
+
+		for (idx, header) in headers.iter().enumerate() {
+			// a column never reaches under the headers next to it
+			let floor = match idx.checked_sub(1) {
+				Some(i) => headers[i].position.x(),
+				None => marker.map_or(i64::MIN, |m| m.position.x()),
+			};
+			let ceiling = headers.get(idx + 1).map_or(i64::MAX, |h| h.position.x());
+
+			if let Some((left, right)) = self.column_extent(header, floor, ceiling) {
+				columns.push(
+					TableColumn {
+						header: (*header).to_owned(),
+						column: Vec::new(),
+						left,
+						right,
+						ambiguous: Vec::new(),
+					}
+				);
 			}
 		}
 
@@ -319,20 +1157,86 @@ impl TableObjects {
 			}
 		}
 
-		for i in 0..columns.len() {
-			for text in self.texts() {
-				if text.between_x(columns[i].start(), columns[i].end()) {
-					columns[i].column.push(TableObject::Text(text.clone()));
+		// every text goes to exactly one column, the one it lies deepest in
+		for text in self.texts() {
+			let x = text.position.x();
+
+			let candidates = columns.iter()
+				.enumerate()
+				.filter(|(_, c)| text.between_x(c.start(), c.end()))
+				.map(|(i, c)| (i, (x - c.start()).min(c.end() - x)))
+				.collect::<Vec<(usize, i64)>>();
+
+			if let Some((i, _)) = candidates.iter().max_by_key(|(_, depth)| *depth) {
+				if candidates.len() > 1 {
+					columns[*i].ambiguous.push(text.clone());
 				}
+
+				columns[*i].column.push(TableObject::Text(text.clone()));
 			}
 		}
 
 		columns
 	}
 
-	// fn _lines<'a>(&'a self) -> FilterMap<Iter<'_, TableObject>, fn(&'a TableObject) -> Option<&'a Line<i64>>> {
-	// 	self.0.iter().filter_map(|o| if let TableObject::Line(l) = o {Some(l)} else {None})
-	// }
+	/// the "Block" header of the table
+	fn marker(&self) -> Option<&Text> {
+		block_markers(self.texts(), &self.1).into_iter().next()
+	}
+
+	/// left and right border of the column under `header`, taken from the vertical lines next to it on the header row.
+	/// falls back to the horizontal lines under the header if there are none, never reaches past `floor` or `ceiling`
+	fn column_extent(&self, header: &Text, floor: i64, ceiling: i64) -> Option<(i64, i64)> {
+		let (x, y) = (header.position.x(), header.position.y());
+
+		let borders = self.lines()
+			.filter(|l| l.dx() == 0 && within(y, l.start.y.min(l.end.y), l.start.y.max(l.end.y), 2))
+			.map(|l| l.start.x);
+
+		let left = borders.clone().filter(|b| *b <= x).max();
+		let right = borders.filter(|b| *b > x).min();
+
+		let crossing = self.lines().filter(|l| l.dy() == 0 && within(x, l.start.x, l.end.x, 1));
+
+		let left = left.or_else(|| crossing.clone().map(|l| l.start.x).min())?;
+		let right = right.or_else(|| crossing.map(|l| l.end.x).max())?;
+
+		Some((left.max(floor), right.min(ceiling)))
+	}
+
+	/// the column under the "Block" header, holding the block numbers
+	fn label_column(&self) -> Option<TableColumn> {
+		let header = self.marker()?;
+		let (left, right) = self.column_extent(header, i64::MIN, i64::MAX)?;
+
+		let mut column = TableColumn {
+			header: header.to_owned(),
+			column: self.0.iter()
+				.filter(|o| o.intersects_x_border(header.position.x()))
+				.cloned()
+				.collect(),
+			left,
+			right,
+			ambiguous: Vec::new(),
+		};
+
+		column.lines().next()?;
+
+		column.column.extend(self.texts().filter(|t| t.between_x(left, right)).map(|t| TableObject::Text(t.clone())));
+
+		Some(column)
+	}
+
+	fn bounding_box(&self) -> Option<BoundingBox> {
+		BoundingBox::around(self.0.iter().flat_map(|o| match o {
+			TableObject::Line(l) => vec![(l.start.x, l.start.y), (l.end.x, l.end.y)],
+			TableObject::Text(t) => vec![(t.position.x(), t.position.y())],
+		}))
+	}
+
+	fn lines(&self) -> impl Iterator<Item = &Line> + Clone {
+		self.0.iter().filter_map(|o| if let TableObject::Line(l) = o {Some(l)} else {None})
+	}
 
 	fn texts<'a>(&'a self) -> FilterMap<Iter<'_, TableObject>, fn(&'a TableObject) -> Option<&'a Text>> {
 		self.0.iter().filter_map(|o| if let TableObject::Text(t) = o {Some(t)} else {None})
@@ -342,10 +1246,26 @@ impl TableObjects {
 struct TableColumn {
 	header: Text,
 	column: Vec<TableObject>,
+	/// borders of the column, see `TableObjects::column_extent`
+	left: i64,
+	right: i64,
+	/// texts that also lay inside another column but went to this one
+	ambiguous: Vec<Text>,
 }
 
 impl TableColumn {
-	fn generate_column(&mut self) -> Result<Vec<Vec<String>>, Box<dyn Error>> {
+	fn generate_column(&mut self, scratch: &mut Vec<i64>) -> Result<Vec<Vec<String>>, Box<dyn Error>> {
+		Ok(self.assemble_with(scratch)?.cells.into_iter()
+			.map(|c| c.into_iter().map(|t| t.text).collect())
+			.collect())
+	}
+
+	fn assemble(&mut self) -> Result<AssembledColumn, Box<dyn Error>> {
+		self.assemble_with(&mut Vec::new())
+	}
+
+	/// `scratch` is only used as a buffer, pass the same one for all columns to save allocating it each time
+	fn assemble_with(&mut self, scratch: &mut Vec<i64>) -> Result<AssembledColumn, Box<dyn Error>> {
 		// remove all vertical lines as they are not needed and interfere with the next steps
 		self.column = self.column.drain(..).filter(|o| {
 			!if let TableObject::Line(l) = o {
@@ -355,7 +1275,7 @@ impl TableColumn {
 			}
 		}).collect();
 
-		let mut lines = self.lines().collect::<Vec<&Line<i64>>>();
+		let mut lines = self.lines().collect::<Vec<&Line>>();
 		lines.sort_by(|l1, l2| l2.start.y.cmp(&l1.start.y));
 
 
@@ -370,16 +1290,26 @@ impl TableColumn {
 				l.start.y - n.start.y
 			}).collect::<Vec<i64>>();
 
-		let smallest_space =  {
-			let mut spacing_sorted = spacing.clone();
-			spacing_sorted.sort();
-			spacing_sorted.reverse();
-			spacing_sorted.truncate(6);
-			spacing_sorted[5]
+		// a damaged or partial table, there is no 6th largest space to pick
+		if spacing.len() < 6 {
+			return Err("column has fewer than 7 rulings".into());
+		}
+
+		// the 6th largest space, the 6 block rows are the highest ones
+		let smallest_space = {
+			scratch.clear();
+			scratch.extend_from_slice(&spacing);
+			*scratch.select_nth_unstable_by(5, |a, b| b.cmp(a)).1
 		};
 
 		spacing.push(smallest_space);
 
+		let dropped = lines.iter()
+			.zip(spacing.iter())
+			.filter(|(_, s)| *s < &smallest_space)
+			.map(|(l, _)| l.start.y)
+			.collect();
+
 		let mut cleaned_column = lines.iter()
 			.zip(spacing.iter())
 			.filter(|(_, s)| *s >= &smallest_space)
@@ -398,7 +1328,15 @@ impl TableColumn {
 			return Err("vertical line in vector".into())
 		}
 
-		cleaned_column.sort_by(|l1, l2| l2.y().unwrap().cmp(&l1.y().unwrap()));
+		// top to bottom, texts on the same height left to right so the order of a cell's lines is stable
+		cleaned_column.sort_by_key(|o| (Reverse(o.y().ok()), match o {
+			TableObject::Line(l) => (false, l.start.x),
+			TableObject::Text(t) => (true, t.position.x()),
+		}));
+
+		let rulings = cleaned_column.iter()
+			.filter_map(|o| if let TableObject::Line(l) = o { Some(l.start.y) } else { None })
+			.collect();
 
 		let mut result = vec![Vec::new(); 7];
 
@@ -412,22 +1350,29 @@ impl TableColumn {
 		for object in cleaned_column {
 			match object {
 				TableObject::Line(_) => i += 1,
-				TableObject::Text(t) => result[i as usize].push(t.text),
+				TableObject::Text(t) => result[i as usize].push(t),
 			}
 		}
 
-		Ok(result)
+		Ok(AssembledColumn {
+			left: self.start(),
+			right: self.end(),
+			rulings,
+			dropped,
+			ambiguous: self.ambiguous.clone(),
+			cells: result,
+		})
 	}
 
 	fn start(&self) -> i64 {
-		self.lines().map(|l| l.start.x).min().expect("no lines in field 'column'")
+		self.left
 	}
 
 	fn end(&self) -> i64 {
-		self.lines().map(|l| l.end.x).max().expect("no lines in field 'column'")
+		self.right
 	}
 
-	fn lines<'a>(&'a self) -> FilterMap<Iter<'_, TableObject>, fn(&'a TableObject) -> Option<&'a Line<i64>>> {
+	fn lines<'a>(&'a self) -> FilterMap<Iter<'_, TableObject>, fn(&'a TableObject) -> Option<&'a Line>> {
 		self.column.iter().filter_map(|o| if let TableObject::Line(l) = o {Some(l)} else {None})
 	}
 
@@ -436,21 +1381,135 @@ impl TableColumn {
 	}
 }
 
+/// a column split into its cells, positions are still known
+struct AssembledColumn {
+	left: i64,
+	right: i64,
+	/// y of the horizontal lines separating the cells, top to bottom
+	rulings: Vec<i64>,
+	/// y of the horizontal lines that were too close to their neighbour to separate two rows
+	dropped: Vec<i64>,
+	/// texts that also lay inside another column, see `TableObjects::extract_columns`
+	ambiguous: Vec<Text>,
+	/// the header cell followed by one cell per row
+	cells: Vec<Vec<Text>>,
+}
+
+impl AssembledColumn {
+	/// box of `cells[idx]` in pdf coordinates, the header cell reaches up to its highest text
+	fn cell_bbox(&self, idx: usize) -> BoundingBox {
+		match idx {
+			0 => BoundingBox {
+				left: self.left,
+				bottom: self.rulings[0],
+				right: self.right,
+				top: self.cells[0].iter()
+					.map(|t| t.position.y())
+					.fold(self.rulings[0], i64::max),
+			},
+			_ => BoundingBox {
+				left: self.left,
+				bottom: self.rulings[idx],
+				right: self.right,
+				top: self.rulings[idx - 1],
+			},
+		}
+	}
+}
+
+#[cfg(feature = "schedule")]
 impl SubstitutionPDFExtractor for HbsTableExtractor {
 	fn schedule_from_pdf<R: Read>(pdf: R) -> Result<SubstitutionSchedule, Box<dyn Error>> {
-		let mut extractor = HbsTableExtractor::load_from(pdf)?;
-		let mut entries = HashMap::new();
+		StrategyExtractor::<MarkerStrategy>::schedule_from_pdf(pdf)
+	}
+}
 
-		for column in extractor.extract_tables()?.iter().flatten().flatten() {
-			entries.insert(
-				column[0][0].clone(),
-				SubstitutionColumn::from_2d_vec(column[..6].to_vec())?
-			);
+/// adds the column of a class to the entries of a schedule, following `config.duplicate_classes` if the class is there already
+#[cfg(feature = "schedule")]
+fn add_column(entries: &mut HashMap<String, SubstitutionColumn>, class: String, column: SubstitutionColumn, config: &ExtractorConfig) -> Result<(), ExtractionError> {
+	match entries.entry(class) {
+		Entry::Vacant(entry) => {
+			entry.insert(column);
 		}
+		Entry::Occupied(entry) => match config.duplicate_classes {
+			DuplicateClasses::Merge => {
+				for (block, other) in diff::blocks_mut(entry.into_mut()).into_iter().zip(diff::blocks(&column)) {
+					match (block, other) {
+						(Some(block), Some(other)) => block.0.extend(other.0.iter().cloned()),
+						(block, Some(other)) => *block = Some(other.clone()),
+						(_, None) => (),
+					}
+				}
+			}
+			DuplicateClasses::Reject => return Err(ExtractionError::DuplicateClass(entry.key().clone())),
+		},
+	}
 
-		Ok(SubstitutionSchedule {
-			pdf_issue_date: extractor.extract_date()?,
-			entries,
-		})
+	Ok(())
+}
+
+/// turns the raw tables of a document into a schedule
+#[cfg(feature = "schedule")]
+fn schedule_from_raw_tables(pages: &[RawPage], pdf_issue_date: i64, config: &ExtractorConfig) -> Result<SubstitutionSchedule, Box<dyn Error>> {
+	let mut entries = HashMap::new();
+
+	for column in pages.iter().flatten().flatten() {
+		let (class, substitutions) = config.row_mapper.map_transformed(column, &config.cell_transforms)?;
+
+		add_column(&mut entries, class, substitutions, config)?;
+	}
+
+	Ok(SubstitutionSchedule {
+		pdf_issue_date,
+		entries,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn text(text: &str, x: i64, y: i64) -> Text {
+		Text {
+			text: text.to_owned(),
+			position: Point::new(x, y),
+			font_size: 9.0,
+			horizontal_scaling: 100.0,
+			#[cfg(feature = "provenance")]
+			source: Source::default(),
+		}
+	}
+
+	fn ruling(y: i64) -> TableObject {
+		TableObject::Line(Line::new(Point::new(0, y), Point::new(80, y)))
 	}
-}
\ No newline at end of file
+
+	#[test]
+	fn column_with_five_rows_fails_to_assemble() {
+		let header = text("5a", 5, 200);
+
+		let mut column = TableColumn {
+			column: std::iter::once(TableObject::Text(header.clone()))
+				.chain((0..6).map(|row| ruling(190 - 30 * row)))
+				.collect(),
+			header,
+			left: 0,
+			right: 80,
+			ambiguous: Vec::new(),
+		};
+
+		assert!(column.assemble().is_err());
+	}
+
+	#[test]
+	fn top_rulings_of_stacked_tables() {
+		// two tables of six rows, their times of the last block at 520 and 270
+		let table = |top: i64| std::iter::once(ruling(top))
+			.chain((0..7).map(move |row| ruling(top - 10 - 30 * row)))
+			.chain(std::iter::once(TableObject::Text(text("14:30 - 15:15", 5, top - 180))));
+
+		let page = PageObjects(table(700).chain(table(450)).collect(), Vec::new());
+
+		assert_eq!(page.top_rulings(&page.bottom_markers(&BottomMarker::default())), vec![450, 700]);
+	}
+}
@@ -0,0 +1,122 @@
+use std::error::Error;
+use std::ops::Range;
+use std::sync::Arc;
+
+use substitution_common::SubstitutionColumn;
+
+use crate::convert::substitution_column;
+use crate::RawColumn;
+
+/// runs on every block cell right before it is turned into a `Substitution`, e.g. to normalize room codes
+pub type CellTransform = Arc<dyn Fn(&mut Vec<String>) + Send + Sync>;
+
+/// what happens if a class has a column in more than one table, e.g. because its table was split across pages
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum DuplicateClasses {
+	/// the blocks of the columns are merged, the texts of a block that is set in both are joined in table order
+	#[default]
+	Merge,
+	/// fail with `ExtractionError::DuplicateClass`
+	Reject,
+}
+
+/// decides which cells of a raw column become the class name and the blocks of a `SubstitutionColumn`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct RowMapper {
+	/// index of the cell whose first text is the class name
+	pub header_row: usize,
+	/// the cells that become blocks 1 to 6, in order. the six cells below the header by default
+	pub block_rows: Range<usize>,
+}
+
+impl Default for RowMapper {
+	fn default() -> Self {
+		Self {
+			header_row: 0,
+			block_rows: 1..7,
+		}
+	}
+}
+
+impl RowMapper {
+	pub fn class_name<'a>(&self, column: &'a RawColumn) -> Result<&'a str, Box<dyn Error>> {
+		column.get(self.header_row)
+			.ok_or_else(|| format!("column has no header row {}", self.header_row))?
+			.first()
+			.map(String::as_str)
+			.ok_or_else(|| "header cell is empty".into())
+	}
+
+	pub fn map(&self, column: &RawColumn) -> Result<(String, SubstitutionColumn), Box<dyn Error>> {
+		self.map_transformed(column, &[])
+	}
+
+	/// like `map` but runs `transforms` in order over every block cell first
+	pub fn map_transformed(&self, column: &RawColumn, transforms: &[CellTransform]) -> Result<(String, SubstitutionColumn), Box<dyn Error>> {
+		let class = self.class_name(column)?;
+		let blocks = self.blocks(column, transforms)?;
+
+		Ok((class.to_owned(), substitution_column(blocks)?))
+	}
+
+	/// the cells of `block_rows` with `transforms` applied, except to the header cell if it is one of them
+	fn blocks(&self, column: &RawColumn, transforms: &[CellTransform]) -> Result<Vec<Vec<String>>, Box<dyn Error>> {
+		let class = self.class_name(column)?;
+
+		let blocks = column.get(self.block_rows.clone())
+			.ok_or_else(|| format!(
+				"column of {class} has {} cells but rows {}..{} are mapped to blocks",
+				column.len(),
+				self.block_rows.start,
+				self.block_rows.end,
+			))?;
+
+		let mut blocks = blocks.to_vec();
+
+		// the class name is not a substitution
+		let blocks_only = self.block_rows.clone().zip(&mut blocks).filter(|(row, _)| *row != self.header_row);
+
+		for (_, cell) in blocks_only {
+			for transform in transforms {
+				transform(cell);
+			}
+		}
+
+		Ok(blocks)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn column(cells: &[&[&str]]) -> RawColumn {
+		cells.iter().map(|c| c.iter().map(|l| (*l).to_owned()).collect()).collect()
+	}
+
+	#[test]
+	fn default_maps_the_six_cells_below_the_header() {
+		let column = column(&[&["10c"], &[], &[], &[], &["En"], &[], &["Entfall"]]);
+		let blocks = RowMapper::default().blocks(&column, &[]).unwrap();
+
+		assert_eq!(blocks, column[1..].to_vec());
+	}
+
+	#[test]
+	fn transforms_leave_the_header_alone() {
+		let column = column(&[&["10c"], &["en"], &[], &[], &[], &[], &[]]);
+		let uppercase: CellTransform = Arc::new(|cell: &mut Vec<String>| cell.iter_mut().for_each(|l| *l = l.to_uppercase()));
+
+		let mapper = RowMapper {
+			header_row: 0,
+			block_rows: 0..6,
+		};
+
+		let blocks = mapper.blocks(&column, &[uppercase]).unwrap();
+
+		assert_eq!(blocks[0], vec!["10c".to_owned()]);
+		assert_eq!(blocks[1], vec!["EN".to_owned()]);
+	}
+}
@@ -0,0 +1,131 @@
+use regex::Regex;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// one way to find where tables start, `ExtractorConfig::top_boundaries` tries them in order
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum TopBoundary {
+	/// the "Block" header of the label column
+	BlockHeader,
+	/// the highest long horizontal line above each bottom marker, for plans where the header went missing.
+	/// a line is long if it spans at least half of the longest horizontal line on the page
+	TopRuling,
+}
+
+/// what ends a table at the bottom, tables start at their "Block" header
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum BottomMarker {
+	/// texts matching the pattern, the table ends at the first horizontal line below them.
+	/// the default is the start time of the last block, "15:15"
+	Text(Regex),
+	/// the lowest horizontal line of the page, e.g. for plans ending with a separator line,
+	/// only works for pages with a single table
+	LowestLine,
+}
+
+impl Default for BottomMarker {
+	fn default() -> Self {
+		Self::Text(Regex::new("15:15").expect("invalid builtin regex"))
+	}
+}
+
+/// the y of the top and bottom marker of every table on a page, and the markers left without a partner
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct MarkerPairs {
+	/// (top, bottom), lowest table first
+	pub(crate) tables: Vec<(i64, i64)>,
+	pub(crate) unpaired_tops: Vec<i64>,
+	pub(crate) unpaired_bottoms: Vec<i64>,
+}
+
+impl MarkerPairs {
+	/// pairs every top marker with the closest bottom marker below it, as long as that is at most `max_height` away
+	/// and not below the next lower top marker, where the bottom marker would belong to that table
+	pub(crate) fn pair(top_markers: &[i64], bottom_markers: &[i64], max_height: i64) -> Self {
+		let mut tops = top_markers.to_vec();
+		tops.sort_unstable();
+
+		let mut bottoms = bottom_markers.iter().map(|y| (*y, false)).collect::<Vec<(i64, bool)>>();
+		bottoms.sort_unstable();
+
+		let mut pairs = Self::default();
+		let mut floor = i64::MIN;
+
+		for top in tops {
+			let closest = bottoms.iter_mut()
+				.rfind(|(bottom, _)| *bottom > floor && *bottom < top && top - *bottom <= max_height);
+
+			match closest {
+				Some((bottom, paired)) => {
+					*paired = true;
+					pairs.tables.push((top, *bottom));
+				}
+				None => pairs.unpaired_tops.push(top),
+			}
+
+			floor = top;
+		}
+
+		pairs.unpaired_bottoms = bottoms.into_iter()
+			.filter(|(_, paired)| !paired)
+			.map(|(bottom, _)| bottom)
+			.collect();
+
+		pairs
+	}
+
+	pub(crate) fn is_complete(&self) -> bool {
+		self.unpaired_tops.is_empty() && self.unpaired_bottoms.is_empty()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn pairs_stacked_tables() {
+		let pairs = MarkerPairs::pair(&[700, 400], &[450, 150], 700);
+
+		assert_eq!(pairs.tables, vec![(400, 150), (700, 450)]);
+		assert!(pairs.is_complete());
+	}
+
+	#[test]
+	fn missing_bottom_marker_only_loses_its_table() {
+		// the lower table's marker can't be taken by the upper one
+		let pairs = MarkerPairs::pair(&[700, 400], &[450], 700);
+
+		assert_eq!(pairs.tables, vec![(700, 450)]);
+		assert_eq!(pairs.unpaired_tops, vec![400]);
+		assert!(pairs.unpaired_bottoms.is_empty());
+	}
+
+	#[test]
+	fn extra_bottom_marker_is_left_over() {
+		let pairs = MarkerPairs::pair(&[700], &[450, 300], 700);
+
+		assert_eq!(pairs.tables, vec![(700, 450)]);
+		assert_eq!(pairs.unpaired_bottoms, vec![300]);
+	}
+
+	#[test]
+	fn legend_marker_without_table_is_left_over() {
+		// the "15:15" in the example of a legend page, far below the only table of the page
+		let pairs = MarkerPairs::pair(&[800], &[650], 100);
+
+		assert!(pairs.tables.is_empty());
+		assert_eq!(pairs.unpaired_tops, vec![800]);
+		assert_eq!(pairs.unpaired_bottoms, vec![650]);
+
+		// and on a page of its own
+		let pairs = MarkerPairs::pair(&[], &[300], 700);
+
+		assert!(pairs.tables.is_empty());
+		assert_eq!(pairs.unpaired_bottoms, vec![300]);
+	}
+}
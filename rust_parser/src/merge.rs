@@ -0,0 +1,62 @@
+use std::error::Error;
+
+use substitution_common::SubstitutionSchedule;
+
+use crate::diff::{blocks, blocks_mut};
+
+/// overlays the partial re-publications of a day, like an afternoon update with only the changed classes,
+/// onto its full plan
+#[derive(Clone, Debug, Default)]
+pub struct SubstitutionScheduleMerge {
+	/// revision time and schedule, in the order they were added
+	revisions: Vec<(i64, SubstitutionSchedule)>,
+}
+
+impl SubstitutionScheduleMerge {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// adds a full or partial plan, `revised` is when it was published, e.g. in milliseconds since the epoch
+	pub fn add(&mut self, revised: i64, schedule: SubstitutionSchedule) -> &mut Self {
+		self.revisions.push((revised, schedule));
+		self
+	}
+
+	/// per class and block the cell of the newest revision that has one, of two revisions at the same time
+	/// the one added last wins. a partial plan only lists changed cells, so it never clears a cell.
+	/// fails without revisions or if they are for different days
+	pub fn merge(&self) -> Result<SubstitutionSchedule, Box<dyn Error>> {
+		let mut revisions = self.revisions.iter().collect::<Vec<_>>();
+		revisions.sort_by_key(|(revised, _)| *revised);
+
+		let (_, first) = revisions.first().ok_or("no revisions to merge")?;
+
+		let mut merged = SubstitutionSchedule {
+			pdf_issue_date: first.pdf_issue_date,
+			entries: Default::default(),
+		};
+
+		for (_, schedule) in revisions {
+			if schedule.pdf_issue_date != merged.pdf_issue_date {
+				return Err(format!(
+					"can't merge the plans of {} and {}",
+					merged.pdf_issue_date,
+					schedule.pdf_issue_date,
+				).into());
+			}
+
+			for (class, column) in &schedule.entries {
+				let target = merged.entries.entry(class.clone()).or_default();
+
+				for (target, block) in blocks_mut(target).into_iter().zip(blocks(column)) {
+					if let Some(block) = block {
+						*target = Some(block.clone());
+					}
+				}
+			}
+		}
+
+		Ok(merged)
+	}
+}
@@ -0,0 +1,51 @@
+use lopdf::Document;
+
+use crate::decoder::{self, WinAnsiDecoder};
+
+/// the entries of the document information dictionary, `None` if the pdf doesn't set them
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct DocumentMetadata {
+	pub page_count: usize,
+	pub title: Option<String>,
+	pub author: Option<String>,
+	pub creator: Option<String>,
+	pub producer: Option<String>,
+	/// raw pdf date string like "D:20220912073000+02'00'"
+	pub creation_date: Option<String>,
+	/// base font names of the font resources of all pages, sorted and without duplicates
+	pub fonts: Vec<String>,
+}
+
+impl DocumentMetadata {
+	pub(crate) fn read(document: &Document) -> Self {
+		let info = document.trailer.get(b"Info")
+			.and_then(|info| document.dereference(info))
+			.and_then(|(_, info)| info.as_dict())
+			.ok();
+
+		let entry = |key: &[u8]| info
+			.and_then(|info| info.get(key).ok())
+			.and_then(|value| value.as_str().ok())
+			.map(|bytes| decoder::decode_shown(&WinAnsiDecoder, None, bytes));
+
+		let mut fonts = document.get_pages()
+			.into_values()
+			.flat_map(|page| document.get_page_fonts(page).into_values())
+			.filter_map(|font| Some(String::from_utf8_lossy(font.get(b"BaseFont").ok()?.as_name().ok()?).into_owned()))
+			.collect::<Vec<String>>();
+
+		fonts.sort();
+		fonts.dedup();
+
+		Self {
+			page_count: document.get_pages().len(),
+			title: entry(b"Title"),
+			author: entry(b"Author"),
+			creator: entry(b"Creator"),
+			producer: entry(b"Producer"),
+			creation_date: entry(b"CreationDate"),
+			fonts,
+		}
+	}
+}
@@ -0,0 +1,347 @@
+use std::error::Error;
+use std::ops::RangeInclusive;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::geometry::{approx_eq, within, BoundingBox};
+use crate::postprocess;
+use crate::{AssembledColumn, HbsTableExtractor, Text};
+
+/// texts closer than this to a cell border might belong to the neighbouring cell
+const BORDER_TOLERANCE: i64 = 2;
+
+/// a detected table with its geometry
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[non_exhaustive]
+pub struct Table {
+	/// index of the page the table is on, counting from 0
+	pub page: usize,
+	/// the closest section heading above the table, possibly on an earlier page
+	pub section: Option<String>,
+	pub bbox: Option<BoundingBox>,
+	/// the "Block" column, one label per row below the header, empty if it could not be assembled
+	pub row_labels: Vec<RowLabel>,
+	pub columns: Vec<Column>,
+	/// the lowest confidence of all cells, carrying all their flags
+	pub confidence: Confidence,
+}
+
+/// a cell of the "Block" column
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[non_exhaustive]
+pub struct RowLabel {
+	/// the first number in the cell, as printed
+	pub block: Option<u32>,
+	pub lines: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[non_exhaustive]
+pub struct Column {
+	pub header: Cell,
+	/// one cell per row below the header
+	pub cells: Vec<Cell>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[non_exhaustive]
+pub struct Cell {
+	/// the text fragments of the cell, top to bottom and left to right, after post-processing,
+	/// the verbatim texts stay in `raw`
+	pub lines: Vec<String>,
+	/// position of each of `lines` in `raw`, stable between extractions of the same layout to join lines on
+	pub sequence: Vec<usize>,
+	/// the text fragments exactly as they are in the pdf
+	pub raw: Vec<String>,
+	/// where each of `raw` was drawn
+	#[cfg(feature = "provenance")]
+	pub sources: Vec<crate::Source>,
+	pub bbox: BoundingBox,
+	pub confidence: Confidence,
+}
+
+/// what one class has in a block, see `Table::by_block`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[non_exhaustive]
+pub struct BlockEntry {
+	/// the first line of the column header
+	pub class: String,
+	/// see `Table::section`
+	pub section: Option<String>,
+	pub lines: Vec<String>,
+}
+
+/// consecutive cells of a column with the same text, see `Column::coalesced`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RangedCell {
+	/// rows below the header, counting from 0
+	pub rows: RangeInclusive<usize>,
+	pub lines: Vec<String>,
+}
+
+/// how sure the extractor is that a cell or table was read correctly
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Confidence {
+	/// between 0.0 (guessed) and 1.0 (no heuristic was needed)
+	pub score: f32,
+	/// the reasons the score was lowered
+	pub flags: Vec<ConfidenceFlag>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[non_exhaustive]
+pub enum ConfidenceFlag {
+	/// a horizontal line inside the cell was ignored because it was too close to its neighbour,
+	/// so the cell might be made of two merged rows
+	MergedRows,
+	/// a text lies on the border of the cell and might belong to the neighbouring cell
+	TextOnBorder,
+	/// a text of the cell also lay inside another column of the table and was assigned to this one
+	/// because it lies deeper in it
+	SharedWithOtherColumn,
+}
+
+impl ConfidenceFlag {
+	fn penalty(&self) -> f32 {
+		match self {
+			Self::MergedRows => 0.3,
+			Self::TextOnBorder => 0.2,
+			Self::SharedWithOtherColumn => 0.4,
+		}
+	}
+}
+
+impl Confidence {
+	fn from_flags(flags: Vec<ConfidenceFlag>) -> Self {
+		Self {
+			score: flags.iter().fold(1.0, |score, f| score - f.penalty()).max(0.0),
+			flags,
+		}
+	}
+
+	/// combines several confidences into the one of the thing they are part of
+	fn lowest<'a, I: IntoIterator<Item = &'a Confidence>>(confidences: I) -> Self {
+		let mut flags = Vec::new();
+		let mut score: f32 = 1.0;
+
+		for confidence in confidences {
+			score = score.min(confidence.score);
+
+			for flag in &confidence.flags {
+				if !flags.contains(flag) {
+					flags.push(*flag);
+				}
+			}
+		}
+
+		Self { score, flags }
+	}
+}
+
+impl HbsTableExtractor {
+	/// like `extract_tables` but keeps geometry and rates every cell
+	pub fn extract_typed_tables(&mut self) -> Result<Vec<Table>, Box<dyn Error>> {
+		let observer = &self.config.observer;
+		let cancellation = &self.config.cancellation;
+		let mut tables = Vec::new();
+		let mut scratch = Vec::new();
+		// the last heading of the pages so far
+		let mut section = None;
+
+		for (page_idx, page) in self.pages.iter().enumerate() {
+			cancellation.check()?;
+
+			let headings = self.config.section_headings.find(page);
+
+			let table_objects = page.extract_table_objects(&self.config)?;
+			observer.tables_detected(page_idx, table_objects.len());
+
+			for (table_idx, table) in table_objects.iter().enumerate() {
+				let mut assembled = Vec::new();
+
+				for mut column in table.extract_columns() {
+					cancellation.check()?;
+					assembled.push(column.assemble_with(&mut scratch)?);
+				}
+
+				let mut columns = assembled.iter()
+					.map(Column::from_assembled)
+					.collect::<Vec<Column>>();
+
+				for column in &mut columns {
+					column.header.bbox = self.exposed(page_idx, column.header.bbox);
+
+					for (block, cell) in column.cells.iter_mut().enumerate() {
+						cell.bbox = self.exposed(page_idx, cell.bbox);
+
+						// the same rules as the raw tables, the sequence follows the lines it belongs to
+						let rules = &self.config.post_processing;
+						let kept = postprocess::kept_texts(rules, &self.config.placeholders, &column.header.lines, block, &cell.lines);
+
+						(cell.lines, cell.sequence) = cell.lines.drain(..)
+							.zip(cell.sequence.drain(..))
+							.zip(kept)
+							.filter_map(|(line, kept)| kept.then_some(line))
+							.unzip();
+					}
+				}
+
+				observer.columns_assembled(page_idx, table_idx, columns.len());
+
+				let top = table.bounding_box().map_or(i64::MAX, |b| b.top);
+
+				let row_labels = table.label_column()
+					.and_then(|mut c| c.assemble_with(&mut scratch).ok())
+					.map(|c| c.cells[1..].iter().map(|texts| RowLabel::new(texts)).collect())
+					.unwrap_or_default();
+
+				tables.push(Table {
+					page: page_idx,
+					section: headings.iter()
+						.rev()
+						.find(|(y, _)| *y > top)
+						.map(|(_, heading)| heading.clone())
+						.or_else(|| section.clone()),
+					bbox: table.bounding_box().map(|b| self.exposed(page_idx, b)),
+					row_labels,
+					confidence: Confidence::lowest(columns.iter().flat_map(|c| &c.cells).map(|c| &c.confidence)),
+					columns,
+				});
+			}
+
+			if let Some((_, heading)) = headings.last() {
+				section = Some(heading.clone());
+			}
+		}
+
+		Ok(tables)
+	}
+}
+
+impl Table {
+	/// row of the block with the printed number `block`, by the row labels if there are any, otherwise by position
+	pub fn row_of_block(&self, block: u32) -> Option<usize> {
+		if self.row_labels.is_empty() {
+			return (block as usize).checked_sub(1);
+		}
+
+		self.row_labels.iter().position(|l| l.block == Some(block))
+	}
+
+	/// the cell of a column for the block with the printed number `block`
+	pub fn cell(&self, column: usize, block: u32) -> Option<&Cell> {
+		self.columns.get(column)?.cells.get(self.row_of_block(block)?)
+	}
+
+	/// the printed numbers of all blocks, counted from 1 by position if the table has no row labels
+	pub fn blocks(&self) -> Vec<u32> {
+		if self.row_labels.is_empty() {
+			let rows = self.columns.iter().map(|c| c.cells.len()).max().unwrap_or(0);
+			return (1..=rows as u32).collect();
+		}
+
+		self.row_labels.iter().filter_map(|l| l.block).collect()
+	}
+
+	/// every class with something in the block with the printed number `block`, left to right.
+	/// columns without a header are left out
+	pub fn by_block(&self, block: u32) -> Vec<BlockEntry> {
+		let row = match self.row_of_block(block) {
+			Some(row) => row,
+			None => return Vec::new(),
+		};
+
+		self.columns.iter()
+			.filter_map(|column| {
+				let cell = column.cells.get(row).filter(|c| !c.lines.is_empty())?;
+
+				Some(BlockEntry {
+					class: column.header.lines.first()?.clone(),
+					section: self.section.clone(),
+					lines: cell.lines.clone(),
+				})
+			})
+			.collect()
+	}
+}
+
+impl RowLabel {
+	fn new(texts: &[Text]) -> Self {
+		let lines = texts.iter().map(|t| t.text.clone()).collect::<Vec<String>>();
+
+		let block = lines.iter()
+			.flat_map(|l| l.split(|c: char| !c.is_ascii_digit()))
+			.find(|n| !n.is_empty())
+			.and_then(|n| n.parse().ok());
+
+		Self { block, lines }
+	}
+}
+
+impl Column {
+	/// the cells with runs of identical rows merged, so three blocks with the same substitution become one entry
+	pub fn coalesced(&self) -> Vec<RangedCell> {
+		let mut ranged: Vec<RangedCell> = Vec::new();
+
+		for (row, cell) in self.cells.iter().enumerate() {
+			match ranged.last_mut() {
+				Some(last) if last.lines == cell.lines => last.rows = *last.rows.start()..=row,
+				_ => ranged.push(RangedCell {
+					rows: row..=row,
+					lines: cell.lines.clone(),
+				}),
+			}
+		}
+
+		ranged
+	}
+
+	fn from_assembled(column: &AssembledColumn) -> Self {
+		let cells = column.cells.iter()
+			.enumerate()
+			.skip(1)
+			.map(|(idx, texts)| Cell::new(texts, column.cell_bbox(idx), column))
+			.collect();
+
+		Self {
+			header: Cell::new(&column.cells[0], column.cell_bbox(0), column),
+			cells,
+		}
+	}
+}
+
+impl Cell {
+	fn new(texts: &[Text], bbox: BoundingBox, column: &AssembledColumn) -> Self {
+		let mut flags = Vec::new();
+
+		if column.dropped.iter().any(|y| within(*y, bbox.bottom, bbox.top, 0)) {
+			flags.push(ConfidenceFlag::MergedRows);
+		}
+
+		if texts.iter().any(|t| column.rulings.iter().any(|y| approx_eq(t.position.y(), *y, BORDER_TOLERANCE))) {
+			flags.push(ConfidenceFlag::TextOnBorder);
+		}
+
+		if texts.iter().any(|t| column.ambiguous.contains(t)) {
+			flags.push(ConfidenceFlag::SharedWithOtherColumn);
+		}
+
+		Self {
+			lines: texts.iter().map(|t| t.text.clone()).collect(),
+			sequence: (0..texts.len()).collect(),
+			raw: texts.iter().map(|t| t.text.clone()).collect(),
+			#[cfg(feature = "provenance")]
+			sources: texts.iter().map(|t| t.source).collect(),
+			bbox,
+			confidence: Confidence::from_flags(flags),
+		}
+	}
+}
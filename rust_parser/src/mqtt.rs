@@ -0,0 +1,139 @@
+use std::error::Error;
+use std::thread;
+use std::time::Duration;
+
+use rumqttc::{Client, MqttOptions, QoS};
+use serde_json::json;
+use substitution_common::SubstitutionSchedule;
+
+use crate::date::Date;
+use crate::diff::CellDifference;
+use crate::store::{ScheduleStore, StoreEvent};
+
+/// wait before the connection to the broker is retried
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+/// messages that are queued before a publish blocks
+const QUEUE_CAPACITY: usize = 64;
+
+/// where a `MqttPublisher` connects and publishes to.
+/// `{date}` in a topic is replaced by the day of the plan as yyyy-mm-dd
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct MqttSettings {
+	pub host: String,
+	pub port: u16,
+	pub client_id: String,
+	/// gets the whole schedule as json, retained so displays that connect later get the plan right away
+	pub schedule_topic: String,
+	/// gets the changed cells of every new revision as json
+	pub changes_topic: String,
+	pub qos: MqttQos,
+}
+
+/// the delivery guarantee of the published messages
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MqttQos {
+	AtMostOnce,
+	AtLeastOnce,
+	ExactlyOnce,
+}
+
+impl Default for MqttSettings {
+	fn default() -> Self {
+		Self {
+			host: "localhost".to_owned(),
+			port: 1883,
+			client_id: "hbs-table-extractor".to_owned(),
+			schedule_topic: "substitutions/{date}/schedule".to_owned(),
+			changes_topic: "substitutions/{date}/changes".to_owned(),
+			qos: MqttQos::AtLeastOnce,
+		}
+	}
+}
+
+impl From<MqttQos> for QoS {
+	fn from(qos: MqttQos) -> Self {
+		match qos {
+			MqttQos::AtMostOnce => QoS::AtMostOnce,
+			MqttQos::AtLeastOnce => QoS::AtLeastOnce,
+			MqttQos::ExactlyOnce => QoS::ExactlyOnce,
+		}
+	}
+}
+
+/// publishes schedules and their changes to an mqtt broker, e.g. for the displays of a school
+pub struct MqttPublisher {
+	client: Client,
+	settings: MqttSettings,
+}
+
+impl MqttPublisher {
+	/// the connection is kept up by a background thread, which reconnects when the broker goes away
+	/// and keeps the messages published in the meantime queued
+	pub fn connect(settings: MqttSettings) -> Self {
+		let options = MqttOptions::new(settings.client_id.clone(), settings.host.clone(), settings.port);
+		let (client, mut connection) = Client::new(options, QUEUE_CAPACITY);
+
+		thread::spawn(move || {
+			for notification in connection.iter() {
+				if notification.is_err() {
+					thread::sleep(RECONNECT_DELAY);
+				}
+			}
+		});
+
+		Self { client, settings }
+	}
+
+	pub fn publish_schedule(&self, schedule: &SubstitutionSchedule) -> Result<(), Box<dyn Error>> {
+		let date = Date::from_timestamp_millis(schedule.pdf_issue_date)
+			.ok_or_else(|| format!("issue date {} is out of range", schedule.pdf_issue_date))?;
+
+		self.publish(&self.settings.schedule_topic, date, true, serde_json::to_vec(schedule)?)
+	}
+
+	/// the differences as a json array of `{class, block, old, new}`, blocks counting from 1 like in the plan
+	pub fn publish_changes(&self, date: Date, differences: &[CellDifference]) -> Result<(), Box<dyn Error>> {
+		let changes = differences.iter()
+			.map(|d| json!({
+				"class": d.class,
+				"block": d.block.map(|b| b + 1),
+				"old": d.old,
+				"new": d.new,
+			}))
+			.collect::<Vec<_>>();
+
+		self.publish(&self.settings.changes_topic, date, false, serde_json::to_vec(&changes)?)
+	}
+
+	/// publishes what the events of `store` changed: the schedule of added days, the schedule and changes
+	/// of changed days, and clears the retained schedule of expired days
+	pub fn publish_events(&self, store: &ScheduleStore, events: &[StoreEvent]) -> Result<(), Box<dyn Error>> {
+		for event in events {
+			match event {
+				StoreEvent::Added { date } => {
+					if let Some(schedule) = store.get(*date) {
+						self.publish_schedule(schedule)?;
+					}
+				}
+				StoreEvent::Changed { date, differences } => {
+					if let Some(schedule) = store.get(*date) {
+						self.publish_schedule(schedule)?;
+					}
+
+					self.publish_changes(*date, differences)?;
+				}
+				// an empty retained message removes the retained one
+				StoreEvent::Expired { date } => self.publish(&self.settings.schedule_topic, *date, true, Vec::new())?,
+			}
+		}
+
+		Ok(())
+	}
+
+	fn publish(&self, topic: &str, date: Date, retain: bool, payload: Vec<u8>) -> Result<(), Box<dyn Error>> {
+		let topic = topic.replace("{date}", &format!("{:04}-{:02}-{:02}", date.year(), date.month(), date.day()));
+
+		Ok(self.client.publish(topic, self.settings.qos.into(), retain, payload)?)
+	}
+}
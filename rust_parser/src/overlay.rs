@@ -0,0 +1,90 @@
+use std::error::Error;
+use std::fmt::Write;
+
+use crate::HbsTableExtractor;
+
+/// width of the overlay if the page has nothing further right, A4
+const MIN_WIDTH: i64 = 595;
+
+impl HbsTableExtractor {
+	/// an svg of the page with its lines and texts in grey, the detected tables in red and the cells of
+	/// every assembled column in blue, for seeing at a glance why a layout doesn't extract.
+	/// if the table detection fails the page is drawn without tables and the error is written on top
+	pub fn debug_svg(&self, page: usize) -> Result<String, Box<dyn Error>> {
+		let objects = self.pages.get(page).ok_or_else(|| format!("the document has no page {page}"))?;
+		let height = self.page_heights[page];
+
+		let width = objects.lines()
+			.map(|l| l.start.x.max(l.end.x))
+			.chain(objects.texts().map(|t| t.position.x()))
+			.max()
+			.unwrap_or(0)
+			.max(MIN_WIDTH);
+
+		let mut svg = String::new();
+
+		writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {width} {height}" font-family="sans-serif">"#)?;
+		writeln!(svg, r#"<rect width="{width}" height="{height}" fill="white"/>"#)?;
+
+		// svg grows downwards, the pdf upwards
+		for line in objects.lines() {
+			writeln!(
+				svg,
+				r##"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="#999" stroke-width="0.5"/>"##,
+				line.start.x, height - line.start.y, line.end.x, height - line.end.y,
+			)?;
+		}
+
+		for text in objects.texts() {
+			writeln!(
+				svg,
+				r##"<text x="{}" y="{}" font-size="{}" fill="#666">{}</text>"##,
+				text.position.x(), height - text.position.y(), text.font_size.max(1.0), escape(&text.text),
+			)?;
+		}
+
+		let tables = match objects.extract_table_objects(&self.config) {
+			Ok(tables) => tables,
+			Err(e) => {
+				writeln!(svg, r#"<text x="4" y="12" font-size="10" fill="red">table detection failed: {}</text>"#, escape(&e.to_string()))?;
+				Vec::new()
+			}
+		};
+
+		for table in tables {
+			if let Some(b) = table.bounding_box() {
+				writeln!(
+					svg,
+					r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="red" stroke-width="1.5"/>"#,
+					b.left, height - b.top, b.right - b.left, b.top - b.bottom,
+				)?;
+			}
+
+			for mut column in table.extract_columns() {
+				let assembled = match column.assemble() {
+					Ok(assembled) => assembled,
+					Err(_) => continue,
+				};
+
+				for idx in 0..assembled.cells.len() {
+					let b = assembled.cell_bbox(idx);
+
+					writeln!(
+						svg,
+						r#"<rect x="{}" y="{}" width="{}" height="{}" fill="blue" fill-opacity="0.05" stroke="blue" stroke-width="0.5"/>"#,
+						b.left, height - b.top, b.right - b.left, b.top - b.bottom,
+					)?;
+				}
+			}
+		}
+
+		svg.push_str("</svg>\n");
+
+		Ok(svg)
+	}
+}
+
+/// escapes text for svg and html
+pub fn escape(text: &str) -> String {
+	text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
@@ -0,0 +1,55 @@
+use std::error::Error;
+use std::io::Write;
+use std::sync::Arc;
+
+use parquet::data_type::{ByteArray, ByteArrayType, Int32Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use substitution_common::SubstitutionSchedule;
+
+use crate::export::long_records;
+
+const SCHEMA: &str = "
+message substitution {
+	REQUIRED BYTE_ARRAY date (UTF8);
+	REQUIRED BYTE_ARRAY class (UTF8);
+	REQUIRED INT32 block;
+	REQUIRED BYTE_ARRAY text (UTF8);
+}
+";
+
+/// writes the schedules in the long format of `write_long_csv` as a single parquet row group
+pub fn write_long_parquet<W: Write + Send>(schedules: &[SubstitutionSchedule], out: W) -> Result<(), Box<dyn Error>> {
+	let records = schedules.iter().flat_map(long_records).collect::<Vec<_>>();
+	let text = |f: fn(&_) -> &String| records.iter().map(|r| ByteArray::from(f(r).as_str())).collect::<Vec<ByteArray>>();
+
+	let mut writer = SerializedFileWriter::new(
+		out,
+		Arc::new(parse_message_type(SCHEMA)?),
+		Arc::new(WriterProperties::builder().build()),
+	)?;
+
+	let mut row_group = writer.next_row_group()?;
+
+	for idx in 0.. {
+		let mut column = match row_group.next_column()? {
+			Some(column) => column,
+			None => break,
+		};
+
+		match idx {
+			0 => column.typed::<ByteArrayType>().write_batch(&text(|r| &r.date), None, None)?,
+			1 => column.typed::<ByteArrayType>().write_batch(&text(|r| &r.class), None, None)?,
+			2 => column.typed::<Int32Type>().write_batch(&records.iter().map(|r| r.block).collect::<Vec<i32>>(), None, None)?,
+			_ => column.typed::<ByteArrayType>().write_batch(&text(|r| &r.text), None, None)?,
+		};
+
+		column.close()?;
+	}
+
+	row_group.close()?;
+	writer.close()?;
+
+	Ok(())
+}
@@ -0,0 +1,76 @@
+use std::error::Error;
+
+use substitution_common::SubstitutionSchedule;
+
+use crate::calendar::SchoolCalendar;
+use crate::date::Date;
+use crate::drift::{DriftTolerance, Fingerprint};
+use crate::metadata::DocumentMetadata;
+use crate::model::{BlockEntry, Table};
+use crate::validation::{GeometryReport, GeometryTolerance, GeometryViolation};
+use crate::{schedule_from_raw_tables, HbsTableExtractor, RawColumn};
+
+/// everything the extractor knows about a document, see `extract_all`
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct Extraction {
+	pub schedule: SubstitutionSchedule,
+	pub tables: Vec<Table>,
+	pub date: Date,
+	/// layout problems found with the default tolerance
+	pub diagnostics: GeometryReport,
+	pub metadata: DocumentMetadata,
+	/// the layout of the document, to notice when the school changes its template
+	pub fingerprint: Fingerprint,
+}
+
+impl HbsTableExtractor {
+	/// the schedule and the typed tables it was built from in one run over the document
+	pub fn extract_all(&mut self) -> Result<Extraction, Box<dyn Error>> {
+		let date = self.filtered_date()?;
+		let tables = self.extract_typed_tables()?;
+
+		// the typed tables went through the same post-processing as `extract_tables`
+		let raw_pages = vec![tables.iter()
+			.map(|table| table.columns.iter()
+				.map(|column| std::iter::once(&column.header)
+					.chain(&column.cells)
+					.map(|cell| cell.lines.clone())
+					.collect::<RawColumn>())
+				.collect())
+			.collect()];
+
+		let fingerprint = self.fingerprint()?;
+		let mut diagnostics = self.validate_geometry(&GeometryTolerance::default())?;
+
+		// first, a new template explains most of the other violations
+		if let Some(template) = &self.config.template {
+			let deviations = fingerprint.layout_drift(template, &DriftTolerance::default());
+
+			if !deviations.is_empty() {
+				diagnostics.violations.insert(0, GeometryViolation::TemplateChanged { deviations });
+			}
+		}
+
+		Ok(Extraction {
+			schedule: schedule_from_raw_tables(&raw_pages, date.timestamp_millis(), &self.config)?,
+			metadata: self.metadata.clone(),
+			diagnostics,
+			fingerprint,
+			tables,
+			date,
+		})
+	}
+}
+
+impl Extraction {
+	/// whether the plan is for a school day, an empty schedule on any other day is no reason to report a missing plan
+	pub fn is_school_day(&self, calendar: &SchoolCalendar) -> bool {
+		calendar.is_school_day(self.date)
+	}
+
+	/// what every class has in the block with the printed number `block`, across all tables
+	pub fn by_block(&self, block: u32) -> Vec<BlockEntry> {
+		self.tables.iter().flat_map(|table| table.by_block(block)).collect()
+	}
+}
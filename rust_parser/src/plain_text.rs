@@ -0,0 +1,127 @@
+use std::error::Error;
+
+use crate::{HbsTableExtractor, Text};
+
+impl HbsTableExtractor {
+	/// the text of `pages`, counting from 0, in reading order. texts on one baseline are joined by a space,
+	/// lines by a newline and pages by a form feed like pdftotext does
+	pub fn extract_plain_text(&self, pages: &[usize]) -> Result<String, Box<dyn Error>> {
+		let mut out = Vec::new();
+
+		for &idx in pages {
+			let page = self.pages.get(idx).ok_or_else(|| format!("page {} does not exist", idx))?;
+
+			let lines = reading_order(page.texts().filter(|t| !t.text.trim().is_empty()).collect())
+				.into_iter()
+				.map(|line| line.iter().map(|t| t.text.trim()).collect::<Vec<&str>>().join(" "))
+				.collect::<Vec<String>>();
+
+			out.push(lines.join("\n"));
+		}
+
+		Ok(out.join("\x0c"))
+	}
+}
+
+/// rough width of a character relative to the font size, the font metrics are not read
+const CHAR_WIDTH: f64 = 0.5;
+
+/// a text with an estimate of the box it covers
+#[derive(Clone, Copy)]
+struct Placed<'a> {
+	text: &'a Text,
+	left: f64,
+	right: f64,
+	bottom: f64,
+	top: f64,
+}
+
+impl<'a> Placed<'a> {
+	fn new(text: &'a Text) -> Self {
+		let (x, y) = (text.position.x() as f64, text.position.y() as f64);
+		let width = text.text.chars().count() as f64 * text.font_size * CHAR_WIDTH * text.horizontal_scaling / 100.0;
+
+		Self { text, left: x, right: x + width, bottom: y, top: y + text.font_size }
+	}
+}
+
+/// the texts grouped into lines in reading order, found by recursive xy-cut: the texts are split at the widest
+/// empty band across them, vertical bands only if they are at least a median font size wide so the spaces
+/// between words are not taken for the gap between two columns
+fn reading_order(texts: Vec<&Text>) -> Vec<Vec<&Text>> {
+	let mut lines = Vec::new();
+	xy_cut(texts.into_iter().map(Placed::new).collect(), &mut lines);
+
+	lines
+}
+
+fn xy_cut<'a>(mut by_y: Vec<Placed<'a>>, lines: &mut Vec<Vec<&'a Text>>) {
+	if by_y.is_empty() {
+		return;
+	}
+
+	let mut sizes = by_y.iter().map(|p| p.text.font_size).collect::<Vec<f64>>();
+	sizes.sort_by(f64::total_cmp);
+	let em = sizes[sizes.len() / 2];
+
+	let mut by_x = by_y.clone();
+	let column_gap = widest_gap(&mut by_x, |p| (p.left, p.right)).filter(|(gap, _)| *gap >= em);
+	// top to bottom
+	let row_gap = widest_gap(&mut by_y, |p| (-p.top, -p.bottom));
+
+	match (column_gap, row_gap) {
+		(Some((column, idx)), row) if row.is_none_or(|(row, _)| column >= row) => {
+			let right = by_x.split_off(idx);
+			xy_cut(by_x, lines);
+			xy_cut(right, lines);
+		}
+		(_, Some((_, idx))) => {
+			let below = by_y.split_off(idx);
+			xy_cut(by_y, lines);
+			xy_cut(below, lines);
+		}
+		_ => lines.extend(baselines(by_y.into_iter().map(|p| p.text).collect())),
+	}
+}
+
+/// sorts `placed` by the start of `extent` and returns the width of the widest empty band between the extents
+/// with the index of the first text after it
+fn widest_gap(placed: &mut [Placed], extent: fn(&Placed) -> (f64, f64)) -> Option<(f64, usize)> {
+	placed.sort_by(|a, b| extent(a).0.total_cmp(&extent(b).0));
+
+	let mut end = f64::NEG_INFINITY;
+	let mut widest: Option<(f64, usize)> = None;
+
+	for (idx, p) in placed.iter().enumerate() {
+		let (start, stop) = extent(p);
+
+		if idx > 0 && start - end > widest.map_or(0.0, |(gap, _)| gap) {
+			widest = Some((start - end, idx));
+		}
+
+		end = end.max(stop);
+	}
+
+	widest
+}
+
+/// texts without an empty band between them grouped into lines, top to bottom and left to right within a line
+fn baselines(mut texts: Vec<&Text>) -> Vec<Vec<&Text>> {
+	texts.sort_by_key(|t| (-t.position.y(), t.position.x()));
+
+	let mut lines: Vec<Vec<&Text>> = Vec::new();
+
+	for text in texts {
+		match lines.last_mut() {
+			// half the font size apart still counts as the same baseline, e.g. for superscripts
+			Some(line) if (line[0].position.y() - text.position.y()) as f64 <= line[0].font_size / 2.0 => line.push(text),
+			_ => lines.push(vec![text]),
+		}
+	}
+
+	for line in &mut lines {
+		line.sort_by_key(|t| t.position.x());
+	}
+
+	lines
+}
@@ -0,0 +1,86 @@
+use regex::Regex;
+
+use crate::RawColumn;
+
+/// cleanup applied to the cells below the header once a column was assembled
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum PostProcessRule {
+	/// removes texts of the first block that only repeat the class header of their column, some plans print the class
+	/// there again. a later block with the same text is a real entry
+	StripHeaderEcho,
+	/// removes texts that are empty or whitespace
+	StripEmpty,
+	/// removes texts made of nothing but dashes, like "-" or "---"
+	StripDashes,
+}
+
+impl PostProcessRule {
+	/// whether `text` survives the rule, `header` are the texts of the column's header cell and `block` the index of
+	/// the cell below it, counting from 0
+	pub fn keeps(&self, header: &[String], block: usize, text: &str) -> bool {
+		let text = text.trim();
+
+		match self {
+			Self::StripHeaderEcho => block != 0 || !header.iter().any(|h| h.trim() == text),
+			Self::StripEmpty => !text.is_empty(),
+			Self::StripDashes => text.is_empty() || text.chars().any(|c| c != '-'),
+		}
+	}
+}
+
+/// which texts of the body cell `block` survive, the ones every rule keeps unless they are all placeholders
+pub(crate) fn kept_texts(rules: &[PostProcessRule], placeholders: &[Regex], header: &[String], block: usize, cell: &[String]) -> Vec<bool> {
+	let kept = cell.iter()
+		.map(|t| rules.iter().all(|r| r.keeps(header, block, t)))
+		.collect::<Vec<bool>>();
+
+	let texts = cell.iter().zip(&kept).filter(|(_, kept)| **kept).map(|(t, _)| t.clone()).collect::<Vec<String>>();
+
+	if is_placeholder(placeholders, &texts) {
+		vec![false; cell.len()]
+	} else {
+		kept
+	}
+}
+
+/// applies the rules and empties the placeholder cells below the header
+pub(crate) fn post_process(rules: &[PostProcessRule], placeholders: &[Regex], column: &mut RawColumn) {
+	if let Some((header, cells)) = column.split_first_mut() {
+		for (block, cell) in cells.iter_mut().enumerate() {
+			let mut kept = kept_texts(rules, placeholders, header, block, cell).into_iter();
+			cell.retain(|_| kept.next().unwrap_or(false));
+		}
+	}
+}
+
+/// a cell is a placeholder if it has text and every text matches one of the patterns
+pub(crate) fn is_placeholder(patterns: &[Regex], cell: &[String]) -> bool {
+	!cell.is_empty() && cell.iter().all(|t| patterns.iter().any(|p| p.is_match(t.trim())))
+}
+
+/// default placeholder patterns, "---" and "frei"
+pub(crate) fn default_placeholders() -> Vec<Regex> {
+	vec![
+		Regex::new(r"^-+$").expect("invalid builtin regex"),
+		Regex::new(r"(?i)^frei$").expect("invalid builtin regex"),
+	]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn column(cells: &[&[&str]]) -> RawColumn {
+		cells.iter().map(|cell| cell.iter().map(|t| (*t).to_owned()).collect()).collect()
+	}
+
+	#[test]
+	fn header_echo_is_only_stripped_from_the_first_block() {
+		let mut raw = column(&[&["5a"], &["5a", "Ma"], &["5a"], &["---"]]);
+
+		post_process(&[PostProcessRule::StripHeaderEcho], &default_placeholders(), &mut raw);
+
+		assert_eq!(raw, column(&[&["5a"], &["Ma"], &["5a"], &[]]));
+	}
+}
@@ -0,0 +1,32 @@
+//! everything needed to extract schedules, `use hbs_table_extractor::prelude::*;`
+
+#[cfg(feature = "schedule")]
+pub use substitution_common::{Substitution, SubstitutionColumn, SubstitutionPDFExtractor, SubstitutionSchedule};
+#[cfg(feature = "tabula")]
+pub use tabula_pdf_parser::{RowLabels, TabulaConfig, TabulaParser};
+
+pub use crate::{
+	BoundingBox,
+	CancellationToken,
+	Cell,
+	Column,
+	Confidence,
+	ConfidenceFlag,
+	ExtractionContext,
+	ExtractionError,
+	ExtractorConfig,
+	HbsTableExtractor,
+	MarkerStrategy,
+	NoProgress,
+	PostProcessRule,
+	ProgressObserver,
+	RawPage,
+	StructureReport,
+	Table,
+	TableDetectionStrategy,
+	TextDecoder,
+};
+#[cfg(feature = "dates")]
+pub use crate::Date;
+#[cfg(feature = "schedule")]
+pub use crate::{RowMapper, StrategyExtractor};
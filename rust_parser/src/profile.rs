@@ -0,0 +1,247 @@
+use std::error::Error;
+use std::fs::{read_to_string, write};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::config::ExtractorConfig;
+use crate::date_extractor::{CreationDate, DateExtractor, MarkerDate};
+use crate::drift::Fingerprint;
+use crate::geometry::{DetectionTolerance, Origin};
+use crate::marker::{BottomMarker, TopBoundary};
+use crate::section::SectionHeadings;
+use crate::validation::GeometryTolerance;
+use crate::HbsTableExtractor;
+
+/// header baseline tolerance while calibrating
+const CALIBRATION_WINDOW: i64 = 8;
+
+/// the tunable settings of an `ExtractorConfig` for one school, kept as toml next to its archive.
+/// missing keys keep their default, which matches `ExtractorConfig::default`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct ExtractionProfile {
+	/// see `ExtractorConfig::top_boundaries`
+	pub top_boundaries: Vec<TopBoundary>,
+	pub tolerance: DetectionTolerance,
+	pub bottom_marker: BottomMarkerSetting,
+	/// prefix of the line with the date of the plan
+	pub date_prefix: String,
+	/// fall back to the creation date of the pdf if there is no date line
+	pub creation_date_fallback: bool,
+	/// see `RowMapper::header_row`
+	pub header_row: usize,
+	/// see `RowMapper::block_rows`
+	pub block_rows: Range<usize>,
+	/// patterns of cells that count as empty
+	pub placeholders: Vec<String>,
+	/// patterns of department headings
+	pub section_headings: Vec<String>,
+	pub section_min_font_size: f64,
+	pub origin: Origin,
+	pub require_content: bool,
+	/// for `validate_geometry`, not part of the `ExtractorConfig`
+	pub geometry: GeometryTolerance,
+	/// the layout of the sample the profile was calibrated with, see `ExtractorConfig::template`
+	pub template: Option<Fingerprint>,
+}
+
+/// `BottomMarker` with the pattern as a string
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum BottomMarkerSetting {
+	Text(String),
+	LowestLine,
+}
+
+impl Default for ExtractionProfile {
+	fn default() -> Self {
+		let config = ExtractorConfig::default();
+
+		Self {
+			top_boundaries: config.top_boundaries,
+			tolerance: config.tolerance,
+			bottom_marker: BottomMarkerSetting::Text("15:15".to_owned()),
+			date_prefix: MarkerDate::default().prefix,
+			creation_date_fallback: false,
+			header_row: config.row_mapper.header_row,
+			block_rows: config.row_mapper.block_rows,
+			placeholders: config.placeholders.iter().map(|p| p.as_str().to_owned()).collect(),
+			section_headings: Vec::new(),
+			section_min_font_size: config.section_headings.min_font_size,
+			origin: config.origin,
+			require_content: config.require_content,
+			geometry: GeometryTolerance::default(),
+			template: None,
+		}
+	}
+}
+
+impl ExtractionProfile {
+	pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+		Self::from_toml(&read_to_string(path)?)
+	}
+
+	pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+		Ok(write(path, self.to_toml()?)?)
+	}
+
+	pub fn from_toml(toml: &str) -> Result<Self, Box<dyn Error>> {
+		Ok(toml::from_str(toml)?)
+	}
+
+	pub fn to_toml(&self) -> Result<String, Box<dyn Error>> {
+		Ok(toml::to_string_pretty(self)?)
+	}
+
+	/// the default config with this profile applied
+	pub fn config(&self) -> Result<ExtractorConfig, Box<dyn Error>> {
+		let mut config = ExtractorConfig::default();
+		self.apply(&mut config)?;
+
+		Ok(config)
+	}
+
+	/// overwrites everything the profile covers, the date extractors are replaced as a whole.
+	/// fails if one of the patterns is not a valid regex
+	pub fn apply(&self, config: &mut ExtractorConfig) -> Result<(), Box<dyn Error>> {
+		config.top_boundaries = self.top_boundaries.clone();
+		config.tolerance = self.tolerance;
+
+		config.bottom_marker = match &self.bottom_marker {
+			BottomMarkerSetting::Text(pattern) => BottomMarker::Text(Regex::new(pattern)?),
+			BottomMarkerSetting::LowestLine => BottomMarker::LowestLine,
+		};
+
+		let mut date_extractors: Vec<Arc<dyn DateExtractor>> = vec![Arc::new(MarkerDate { prefix: self.date_prefix.clone() })];
+
+		if self.creation_date_fallback {
+			date_extractors.push(Arc::new(CreationDate));
+		}
+
+		config.date_extractors = date_extractors;
+		config.row_mapper.header_row = self.header_row;
+		config.row_mapper.block_rows = self.block_rows.clone();
+		config.placeholders = compile(&self.placeholders)?;
+
+		config.section_headings = SectionHeadings {
+			patterns: compile(&self.section_headings)?,
+			min_font_size: self.section_min_font_size,
+		};
+
+		config.origin = self.origin;
+		config.require_content = self.require_content;
+		config.template = self.template.clone();
+
+		Ok(())
+	}
+
+	/// a starting point for a new school, picks the markers under which every page of the sample
+	/// has tables and enables the creation date fallback if the sample has no date line
+	pub fn from_sample(sample: &HbsTableExtractor) -> Self {
+		let mut profile = Self::default();
+
+		let candidates = [
+			(vec![TopBoundary::BlockHeader], profile.bottom_marker.clone()),
+			(vec![TopBoundary::BlockHeader, TopBoundary::TopRuling], profile.bottom_marker.clone()),
+			(vec![TopBoundary::BlockHeader, TopBoundary::TopRuling], BottomMarkerSetting::LowestLine),
+		];
+
+		for (top_boundaries, bottom_marker) in candidates {
+			let candidate = Self {
+				top_boundaries,
+				bottom_marker,
+				..profile.clone()
+			};
+
+			let finds_tables = candidate.config().is_ok_and(|config| sample.pages.iter().all(|page| {
+				page.extract_table_objects(&config).is_ok_and(|tables| !tables.is_empty())
+			}));
+
+			if finds_tables {
+				profile = candidate;
+				break;
+			}
+		}
+
+		let marker = MarkerDate { prefix: profile.date_prefix.clone() };
+		profile.creation_date_fallback = marker.extract(sample).is_err() && CreationDate.extract(sample).is_ok();
+
+		profile
+	}
+
+	/// measures a plan known to extract correctly and sets the tolerances just above what it needs:
+	/// the spread of the header baselines, how far lines lean and how uneven the rows are.
+	/// the layout of the sample becomes the template later plans are compared against
+	pub fn calibrate(&mut self, sample: &HbsTableExtractor) -> Result<(), Box<dyn Error>> {
+		let mut config = self.config()?;
+		// wide enough to find header texts the current tolerance would miss
+		config.tolerance.header_baseline = CALIBRATION_WINDOW;
+
+		let mut baseline_spread = 0;
+		let mut lean = 0;
+		let mut row_deviation = 0;
+
+		for page in &sample.pages {
+			// only lines meant to be horizontal or vertical, not the strokes of a logo
+			lean = page.lines()
+				.map(|l| (l.dx().abs().min(l.dy().abs()), l.dx().abs().max(l.dy().abs())))
+				.filter(|(short, long)| short * 10 < *long)
+				.map(|(short, _)| short)
+				.fold(lean, i64::max);
+
+			for table in page.extract_table_objects(&config)? {
+				let marker = match table.marker() {
+					Some(marker) => marker.position.y(),
+					None => continue,
+				};
+
+				for mut column in table.extract_columns() {
+					baseline_spread = baseline_spread.max((column.header.position.y() - marker).abs());
+
+					let assembled = column.assemble()?;
+
+					let mut heights = assembled.rulings[1..].windows(2)
+						.map(|w| w[0] - w[1])
+						.collect::<Vec<i64>>();
+
+					heights.sort_unstable();
+
+					if let Some(median) = heights.get(heights.len() / 2).copied().filter(|m| *m > 0) {
+						row_deviation = heights.iter()
+							.map(|h| (h - median).abs() * 100 / median)
+							.fold(row_deviation, i64::max);
+					}
+				}
+			}
+		}
+
+		self.tolerance.header_baseline = baseline_spread + 1;
+		self.tolerance.table_margin = baseline_spread.max(lean) + 2;
+		self.geometry.axis_alignment = lean.max(1);
+		self.geometry.row_spacing_percent = row_deviation + 5;
+		self.template = Some(sample.fingerprint()?);
+
+		Ok(())
+	}
+}
+
+impl HbsTableExtractor {
+	/// the loaded pages with `profile` applied to the current config, to try out a tweaked profile
+	/// without loading the pdf again. see `reconfigured` for the settings that still need a reload
+	pub fn re_extract_with(&self, profile: &ExtractionProfile) -> Result<Self, Box<dyn Error>> {
+		let mut config = self.config.clone();
+		profile.apply(&mut config)?;
+
+		self.reconfigured(config)
+	}
+}
+
+fn compile(patterns: &[String]) -> Result<Vec<Regex>, regex::Error> {
+	patterns.iter().map(|p| Regex::new(p)).collect()
+}
@@ -0,0 +1,18 @@
+/// receives progress events while a document is loaded and its tables are extracted,
+/// every method does nothing by default so implementors only pick the events they need
+pub trait ProgressObserver: Send + Sync {
+	/// page `page` (counting from 0) out of `total` pages was decoded
+	fn page_loaded(&self, _page: usize, _total: usize) {}
+
+	/// `count` tables were detected on page `page`
+	fn tables_detected(&self, _page: usize, _count: usize) {}
+
+	/// all `count` columns of table `table` on page `page` were assembled
+	fn columns_assembled(&self, _page: usize, _table: usize, _count: usize) {}
+}
+
+/// the default observer, ignores everything
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoProgress;
+
+impl ProgressObserver for NoProgress {}
@@ -0,0 +1,13 @@
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// where a text was drawn, to get from an extracted value back to the drawing commands of the pdf
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[non_exhaustive]
+pub struct Source {
+	/// counting from 0
+	pub page: usize,
+	/// index of the operation across the content streams of the page, the same as in `dump_operations`
+	pub operation: usize,
+}
@@ -0,0 +1,53 @@
+use std::collections::HashSet;
+#[cfg(feature = "schedule")]
+use std::error::Error;
+#[cfg(feature = "schedule")]
+use std::io::Read;
+use std::time::Duration;
+
+#[cfg(feature = "schedule")]
+use lopdf::Document;
+
+#[cfg(feature = "schedule")]
+use crate::config::ExtractorConfig;
+#[cfg(feature = "schedule")]
+use crate::HbsTableExtractor;
+use crate::{PageObjects, TableObject};
+
+/// the buffers of an extractor that is done, for the next pdf a batch worker loads on the same thread.
+/// keeps the pages and the bytes of the largest pdf seen so far, so it lives no longer than one batch.
+///
+/// only the containers are reused: lopdf still parses every pdf into a new `Document`, every content stream is
+/// still decompressed into a new buffer and every text is a new `String`, as `TextDecoder` hands out owned ones
+#[derive(Default)]
+pub(crate) struct Recycled {
+	pub(crate) pages: Vec<PageObjects>,
+	pub(crate) page_heights: Vec<i64>,
+	pub(crate) decode_times: Vec<Duration>,
+	/// where the objects of a content stream are deduplicated
+	pub(crate) unique: HashSet<TableObject>,
+	/// the content of the last pdf
+	#[cfg(feature = "schedule")]
+	source: Vec<u8>,
+}
+
+/// only the batch workers load this way
+#[cfg(feature = "schedule")]
+impl HbsTableExtractor {
+	/// `load_from_with_config` reusing the buffers in `recycled`, hand them back with `recycle` once done
+	pub(crate) fn load_recycled<R: Read>(mut src: R, config: ExtractorConfig, recycled: &mut Recycled) -> Result<Self, Box<dyn Error>> {
+		recycled.source.clear();
+		src.read_to_end(&mut recycled.source)?;
+
+		let document = Document::load_mem(&recycled.source)?;
+
+		Self::from_document_recycled(&document, config, recycled)
+	}
+
+	/// gives the buffers of the extractor to the next `load_recycled`
+	pub(crate) fn recycle(self, into: &mut Recycled) {
+		into.pages = self.pages;
+		into.page_heights = self.page_heights;
+		into.decode_times = self.decode_times;
+	}
+}
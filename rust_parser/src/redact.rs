@@ -0,0 +1,253 @@
+use std::collections::HashSet;
+use std::error::Error;
+
+use lopdf::content::Content;
+use lopdf::{Dictionary, Document, Object, ObjectId, StringFormat};
+
+use crate::decoder::{self, WinAnsiDecoder};
+
+/// kerning in a TJ array below this, in thousandths of the font size, is taken for a space between words
+const WORD_GAP: f64 = -200.0;
+
+/// replaces every word of the shown strings that is in `words`, e.g. teacher abbreviations, by "***"
+///
+/// the strings are rewritten in the content streams of the pages and of the forms they draw rather than covered up,
+/// so the names can't be copied out of the published pdf either. the strings of a TJ array are matched as one text,
+/// an array with a replaced word is rewritten as a single string. like the default decoder it assumes
+/// WinAnsiEncoding for everything but UTF-16 strings. streams without a replaced word are left as they are.
+/// returns how many words were replaced.
+pub fn redact(document: &mut Document, words: &HashSet<String>) -> Result<usize, Box<dyn Error>> {
+	let mut replaced = 0;
+
+	for object_id in content_streams(document) {
+		let stream = match document.get_object_mut(object_id)?.as_stream_mut() {
+			Ok(stream) => stream,
+			Err(_) => continue,
+		};
+
+		// an unfiltered stream has nothing to decompress
+		let plain = stream.decompressed_content().unwrap_or_else(|_| stream.content.clone());
+		let mut content = Content::decode(&plain)?;
+		let mut changed = false;
+
+		for op in &mut content.operations {
+			if !matches!(op.operator.as_str(), "Tj" | "TJ" | "'" | "\"") {
+				continue;
+			}
+
+			for operand in &mut op.operands {
+				let (text, utf16) = match shown_text(operand) {
+					Some(shown) => shown,
+					None => continue,
+				};
+
+				if let Some((text, count)) = redact_text(&text, words) {
+					let string = Object::String(encode(&text, utf16), StringFormat::Literal);
+
+					*operand = match operand {
+						Object::Array(_) => Object::Array(vec![string]),
+						_ => string,
+					};
+
+					replaced += count;
+					changed = true;
+				}
+			}
+		}
+
+		if changed {
+			stream.set_plain_content(content.encode()?);
+			stream.compress()?;
+		}
+	}
+
+	Ok(replaced)
+}
+
+/// the content streams of every page and of every form xobject reachable from their resources, each once
+fn content_streams(document: &Document) -> Vec<ObjectId> {
+	let mut streams = Vec::new();
+	let mut seen = HashSet::new();
+
+	for page in document.page_iter() {
+		streams.extend(document.get_page_contents(page));
+
+		let (inline, referenced) = document.get_page_resources(page);
+
+		let mut pending = inline.into_iter()
+			.chain(referenced.into_iter().filter_map(|id| document.get_dictionary(id).ok()))
+			.collect::<Vec<&Dictionary>>();
+
+		// forms can draw forms of their own
+		while let Some(resources) = pending.pop() {
+			let xobjects = match resources.get(b"XObject").and_then(|x| document.dereference(x)).and_then(|(_, x)| x.as_dict()) {
+				Ok(xobjects) => xobjects,
+				Err(_) => continue,
+			};
+
+			for (_, xobject) in xobjects.iter() {
+				let id = match xobject.as_reference() {
+					Ok(id) if seen.insert(id) => id,
+					_ => continue,
+				};
+
+				let form = match document.get_object(id).and_then(Object::as_stream) {
+					Ok(form) if form.dict.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"Form") => form,
+					_ => continue,
+				};
+
+				streams.push(id);
+
+				if let Ok((_, resources)) = form.dict.get(b"Resources").and_then(|r| document.dereference(r)) {
+					pending.extend(resources.as_dict().ok());
+				}
+			}
+		}
+	}
+
+	streams
+}
+
+/// the text an operand shows and whether it is UTF-16, the strings of a TJ array joined
+fn shown_text(operand: &Object) -> Option<(String, bool)> {
+	let decode = |bytes: &[u8]| decoder::decode_shown(&WinAnsiDecoder, None, bytes);
+
+	match operand {
+		Object::String(bytes, _) => Some((decode(bytes), bytes.starts_with(&[0xFE, 0xFF]))),
+		Object::Array(array) => {
+			let mut text = String::new();
+			let mut utf16 = None;
+
+			for element in array {
+				match element {
+					Object::String(bytes, _) => {
+						utf16.get_or_insert(bytes.starts_with(&[0xFE, 0xFF]));
+						text.push_str(&decode(bytes));
+					}
+					Object::Integer(offset) if (*offset as f64) < WORD_GAP => text.push(' '),
+					Object::Real(offset) if *offset < WORD_GAP => text.push(' '),
+					_ => {}
+				}
+			}
+
+			utf16.map(|utf16| (text, utf16))
+		}
+		_ => None,
+	}
+}
+
+/// the text with the words replaced and how many there were, `None` if none of them occur
+fn redact_text(text: &str, words: &HashSet<String>) -> Option<(String, usize)> {
+	let mut result = String::with_capacity(text.len());
+	let mut count = 0;
+	let mut rest = text;
+
+	while !rest.is_empty() {
+		let end = rest.find(|c: char| !c.is_alphanumeric()).unwrap_or(rest.len());
+		let (word, after) = rest.split_at(end);
+
+		if words.contains(word) {
+			result.push_str("***");
+			count += 1;
+		} else {
+			result.push_str(word);
+		}
+
+		// the separator following the word, if any
+		let separator = after.chars().next().map_or(0, char::len_utf8);
+		result.push_str(&after[..separator]);
+		rest = &after[separator..];
+	}
+
+	(count > 0).then_some((result, count))
+}
+
+fn encode(text: &str, utf16: bool) -> Vec<u8> {
+	if utf16 {
+		[0xFE, 0xFF].into_iter()
+			.chain(text.encode_utf16().flat_map(u16::to_be_bytes))
+			.collect()
+	} else {
+		Document::encode_text(Some("WinAnsiEncoding"), text)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use lopdf::{dictionary, Stream};
+
+	use super::*;
+
+	/// a one page document drawing `page` itself and `form` through a form xobject
+	fn document(page: &[u8], form: &[u8]) -> (Document, ObjectId, ObjectId) {
+		let mut document = Document::with_version("1.5");
+		let pages_id = document.new_object_id();
+
+		let form_id = document.add_object(Stream::new(dictionary! {
+			"Type" => "XObject",
+			"Subtype" => "Form",
+			"BBox" => vec![0.into(), 0.into(), 100.into(), 100.into()],
+		}, form.to_vec()));
+
+		let mut contents = Stream::new(dictionary! {}, page.to_vec());
+		contents.compress().unwrap();
+		let contents_id = document.add_object(contents);
+
+		let page_id = document.add_object(dictionary! {
+			"Type" => "Page",
+			"Parent" => pages_id,
+			"Contents" => contents_id,
+			"Resources" => dictionary! { "XObject" => dictionary! { "Fm0" => form_id } },
+		});
+
+		document.objects.insert(pages_id, Object::Dictionary(dictionary! {
+			"Type" => "Pages",
+			"Kids" => vec![page_id.into()],
+			"Count" => 1,
+		}));
+
+		let catalog_id = document.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+		document.trailer.set("Root", catalog_id);
+
+		(document, contents_id, form_id)
+	}
+
+	/// what an operand of the first operation after BT of `object_id` shows
+	fn shown(document: &Document, object_id: ObjectId) -> Option<(String, bool)> {
+		let stream = document.get_object(object_id).unwrap().as_stream().unwrap();
+		let plain = stream.decompressed_content().unwrap_or_else(|_| stream.content.clone());
+		shown_text(&Content::decode(&plain).unwrap().operations[1].operands[0])
+	}
+
+	fn words() -> HashSet<String> {
+		["Müller".to_string()].into_iter().collect()
+	}
+
+	#[test]
+	fn names_split_across_a_tj_array_are_replaced() {
+		let (mut document, contents_id, _) = document(b"BT [(M\xfc) -20 (ller) -500 (Mathe)] TJ ET", b"");
+
+		assert_eq!(redact(&mut document, &words()).unwrap(), 1);
+		assert_eq!(shown(&document, contents_id), Some(("*** Mathe".to_string(), false)));
+	}
+
+	#[test]
+	fn names_in_forms_are_replaced() {
+		let (mut document, _, form_id) = document(b"/Fm0 Do", b"BT (M\xfcller) Tj ET");
+
+		assert_eq!(redact(&mut document, &words()).unwrap(), 1);
+		assert_eq!(shown(&document, form_id), Some(("***".to_string(), false)));
+	}
+
+	#[test]
+	fn streams_without_names_stay_compressed() {
+		let (mut document, contents_id, _) = document(&b"BT (Mathe) Tj ET\n".repeat(8), b"");
+		let before = document.get_object(contents_id).unwrap().as_stream().unwrap().content.clone();
+
+		assert_eq!(redact(&mut document, &words()).unwrap(), 0);
+
+		let stream = document.get_object(contents_id).unwrap().as_stream().unwrap();
+		assert_eq!(stream.content, before);
+		assert!(stream.dict.has(b"Filter"));
+	}
+}
@@ -0,0 +1,61 @@
+use std::error::Error;
+
+use regex::Regex;
+
+use crate::model::Table;
+use crate::{HbsTableExtractor, PageObjects};
+
+/// how the headings of department sections like "Gymnasium" are recognised, none by default
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct SectionHeadings {
+	/// a text is a heading if it matches one of these (after trimming) ...
+	pub patterns: Vec<Regex>,
+	/// ... and is set at least this large, headings are usually set larger than the tables
+	pub min_font_size: f64,
+}
+
+impl SectionHeadings {
+	/// y and text of every heading on the page, top to bottom
+	pub(crate) fn find(&self, page: &PageObjects) -> Vec<(i64, String)> {
+		let mut headings = page.texts()
+			.filter(|t| t.font_size >= self.min_font_size)
+			.map(|t| (t.position.y(), t.text.trim()))
+			.filter(|(_, text)| self.patterns.iter().any(|p| p.is_match(text)))
+			.map(|(y, text)| (y, text.to_owned()))
+			.collect::<Vec<(i64, String)>>();
+
+		headings.sort_by(|(y1, _), (y2, _)| y2.cmp(y1));
+		headings
+	}
+}
+
+/// the tables below one heading, up to the next one
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct Section {
+	/// `None` for the tables before the first heading
+	pub heading: Option<String>,
+	pub tables: Vec<Table>,
+}
+
+impl HbsTableExtractor {
+	/// `extract_typed_tables` grouped by the section headings configured in `ExtractorConfig::section_headings`,
+	/// in the order the sections first appear
+	pub fn extract_sections(&mut self) -> Result<Vec<Section>, Box<dyn Error>> {
+		let mut sections: Vec<Section> = Vec::new();
+
+		// tables come bottom to top within a page, so a section is not necessarily contiguous in that order
+		for table in self.extract_typed_tables()? {
+			match sections.iter_mut().find(|s| s.heading == table.section) {
+				Some(section) => section.tables.push(table),
+				_ => sections.push(Section {
+					heading: table.section.clone(),
+					tables: vec![table],
+				}),
+			}
+		}
+
+		Ok(sections)
+	}
+}
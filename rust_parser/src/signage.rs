@@ -0,0 +1,84 @@
+use std::io::{self, Write};
+
+use substitution_common::SubstitutionSchedule;
+
+use crate::export::long_records;
+use crate::labels::Labels;
+use crate::overlay::escape;
+
+/// how the schedule is laid out on a hallway display
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct SignageSettings {
+	/// rows that fit on one screen in the large font, longer schedules are split into pages
+	pub rows_per_page: usize,
+	/// seconds each page is shown before the next one
+	pub rotate_seconds: u32,
+	/// the time of every block, `block_times[0]` for the first one, like "07:45 - 09:15".
+	/// blocks without a time are shown by their number
+	pub block_times: Vec<String>,
+}
+
+impl Default for SignageSettings {
+	fn default() -> Self {
+		Self {
+			rows_per_page: 12,
+			rotate_seconds: 15,
+			block_times: Vec::new(),
+		}
+	}
+}
+
+/// writes the schedule as a standalone full screen html page for displays, one row per class and block
+/// with a substitution, split into pages that rotate on their own.
+///
+/// the rotation is also announced in a `signage-rotation` meta tag with the page count and the seconds per page
+/// for players that rotate themselves, they can show the pages by their `page-<n>` ids, counting from 1
+pub fn write_signage_html<W: Write>(schedule: &SubstitutionSchedule, labels: &Labels, settings: &SignageSettings, mut out: W) -> io::Result<()> {
+	let records = long_records(schedule);
+	let date = records.first().map(|r| r.date.clone()).unwrap_or_default();
+
+	let pages = records.chunks(settings.rows_per_page.max(1)).collect::<Vec<_>>();
+	let page_count = pages.len().max(1);
+
+	writeln!(out, "<!DOCTYPE html>")?;
+	writeln!(out, "<html><head><meta charset=\"utf-8\">")?;
+	writeln!(out, "<meta name=\"signage-rotation\" content=\"pages={page_count}; seconds={}\">", settings.rotate_seconds)?;
+	writeln!(out, "<title>{} {}</title>", escape(&labels.substitution), escape(&date))?;
+	writeln!(out, "<style>{STYLE}</style>")?;
+	writeln!(out, "</head><body>")?;
+
+	if pages.is_empty() {
+		writeln!(out, "<section id=\"page-1\" class=\"page\"><h1>{}</h1><p class=\"empty\">{}</p></section>", escape(&date), escape(&labels.no_substitutions))?;
+	}
+
+	for (page, records) in pages.iter().enumerate() {
+		writeln!(out, "<section id=\"page-{}\" class=\"page\">", page + 1)?;
+		writeln!(out, "<h1>{} {}<span class=\"counter\">{}/{page_count}</span></h1>", escape(&labels.date), escape(&date), page + 1)?;
+		writeln!(out, "<table><tr><th>{}</th><th>{}</th><th>{}</th></tr>", escape(&labels.class), escape(&labels.block), escape(&labels.substitution))?;
+
+		for record in records.iter() {
+			let block = settings.block_times.get(record.block as usize - 1)
+				.cloned()
+				.unwrap_or_else(|| labels.block_number(record.block as usize));
+
+			writeln!(out, "<tr><td>{}</td><td>{}</td><td>{}</td></tr>", escape(&record.class), escape(&block), escape(&record.text))?;
+		}
+
+		writeln!(out, "</table></section>")?;
+	}
+
+	writeln!(out, "<script>{}</script>", ROTATION.replace("{seconds}", &settings.rotate_seconds.max(1).to_string()))?;
+	writeln!(out, "</body></html>")
+}
+
+const STYLE: &str = "html,body{margin:0;height:100%;background:#000;color:#fff;font-family:sans-serif}\
+.page{display:none;padding:2vh 3vw}.page:first-of-type{display:block}\
+h1{font-size:6vh;margin:0 0 2vh}.counter{float:right;color:#888}\
+table{width:100%;border-collapse:collapse;font-size:4.5vh}th{text-align:left;color:#fc0}\
+td,th{padding:1vh 1vw;border-bottom:1px solid #444}.empty{font-size:6vh}";
+
+/// shows the pages one after another, `{seconds}` is replaced by the seconds per page
+const ROTATION: &str = "var pages=document.querySelectorAll('.page'),current=0;\
+if(pages.length>1)setInterval(function(){pages[current].style.display='none';\
+current=(current+1)%pages.length;pages[current].style.display='block';},{seconds}*1000);";
@@ -0,0 +1,55 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use lopdf::Document;
+
+use crate::date::Date;
+use crate::date_extractor::MarkerDate;
+use crate::config::ExtractorConfig;
+use crate::HbsTableExtractor;
+
+/// splits a pdf with the plans of several days into one document per day
+///
+/// a page belongs to the date found on it with `marker`, pages without one continue the plan of the page before
+pub fn split_by_date(document: &Document, marker: &MarkerDate) -> Result<BTreeMap<Date, Document>, Box<dyn Error>> {
+	split_by_date_with_config(document, marker, ExtractorConfig::default())
+}
+
+/// `split_by_date` with the pages read with `config`
+pub(crate) fn split_by_date_with_config(document: &Document, marker: &MarkerDate, config: ExtractorConfig) -> Result<BTreeMap<Date, Document>, Box<dyn Error>> {
+	let extractor = HbsTableExtractor::from_document_with_config(document, config)?;
+	let page_numbers = document.get_pages().into_keys().collect::<Vec<u32>>();
+
+	let mut days: BTreeMap<Date, Vec<u32>> = BTreeMap::new();
+	let mut current = None;
+
+	for (idx, (page, number)) in extractor.pages().iter().zip(&page_numbers).enumerate() {
+		if let Ok(date) = marker.find(page.texts()) {
+			current = Some(date);
+		}
+
+		let date = current.ok_or_else(|| format!("page {} has no date and no dated page before it", idx))?;
+		days.entry(date).or_default().push(*number);
+	}
+
+	let mut documents = BTreeMap::new();
+
+	let filter = extractor.config.date_filter.as_ref();
+
+	for (date, keep) in days.into_iter().filter(|(date, _)| filter.is_none_or(|f| f.keeps(*date))) {
+		let mut day = document.clone();
+
+		let delete = page_numbers.iter()
+			.filter(|n| !keep.contains(n))
+			.copied()
+			.collect::<Vec<u32>>();
+
+		day.delete_pages(&delete);
+		day.prune_objects();
+		day.renumber_objects();
+
+		documents.insert(date, day);
+	}
+
+	Ok(documents)
+}
@@ -0,0 +1,100 @@
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::time::Duration;
+
+use substitution_common::SubstitutionSchedule;
+
+use crate::date::Date;
+use crate::diff::{diff_schedules, CellDifference};
+
+/// the latest plan of every day that hasn't passed yet, for bots that keep their users up to date
+#[derive(Clone, Debug)]
+pub struct ScheduleStore {
+	/// revision time and schedule of every day
+	days: BTreeMap<Date, (i64, SubstitutionSchedule)>,
+	expire_at: Duration,
+}
+
+/// what changed in a `ScheduleStore`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum StoreEvent {
+	/// the first plan for the day
+	Added { date: Date },
+	/// a newer revision of the day's plan with different cells
+	Changed { date: Date, differences: Vec<CellDifference> },
+	/// the day passed and its plan was dropped
+	Expired { date: Date },
+}
+
+impl ScheduleStore {
+	/// plans are dropped once `expire_at` has passed on their day, counted from midnight utc
+	pub fn new(expire_at: Duration) -> Self {
+		Self {
+			days: BTreeMap::new(),
+			expire_at,
+		}
+	}
+
+	/// keeps the schedule if it is the newest revision of its day, `revised` being in milliseconds since the epoch.
+	/// an older or equally old revision is ignored without events
+	pub fn ingest(&mut self, revised: i64, schedule: SubstitutionSchedule) -> Result<Vec<StoreEvent>, Box<dyn Error>> {
+		let date = Date::from_timestamp_millis(schedule.pdf_issue_date)
+			.ok_or_else(|| format!("issue date {} is out of range", schedule.pdf_issue_date))?;
+
+		Ok(match self.days.entry(date) {
+			Entry::Vacant(day) => {
+				day.insert((revised, schedule));
+				vec![StoreEvent::Added { date }]
+			}
+			Entry::Occupied(mut day) => {
+				let (stored_revision, stored) = day.get();
+
+				if *stored_revision >= revised {
+					return Ok(Vec::new());
+				}
+
+				let differences = diff_schedules(stored, &schedule);
+				day.insert((revised, schedule));
+
+				if differences.is_empty() {
+					Vec::new()
+				} else {
+					vec![StoreEvent::Changed { date, differences }]
+				}
+			}
+		})
+	}
+
+	/// drops the plans of the days that are over at `now`, in milliseconds since the epoch
+	pub fn expire(&mut self, now: i64) -> Vec<StoreEvent> {
+		let expire_at = self.expire_at.as_millis() as i64;
+
+		let expired = self.days.keys()
+			.copied()
+			.filter(|date| date.timestamp_millis() + expire_at <= now)
+			.collect::<Vec<Date>>();
+
+		expired.into_iter()
+			.map(|date| {
+				self.days.remove(&date);
+				StoreEvent::Expired { date }
+			})
+			.collect()
+	}
+
+	pub fn get(&self, date: Date) -> Option<&SubstitutionSchedule> {
+		self.days.get(&date).map(|(_, schedule)| schedule)
+	}
+
+	/// the plan of the earliest day still in the store, today's until it expires
+	pub fn current(&self) -> Option<(Date, &SubstitutionSchedule)> {
+		self.days.iter().next().map(|(date, (_, schedule))| (*date, schedule))
+	}
+
+	/// the days in the store, in order
+	pub fn dates(&self) -> impl Iterator<Item = Date> + '_ {
+		self.days.keys().copied()
+	}
+}
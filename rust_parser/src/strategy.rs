@@ -0,0 +1,72 @@
+use std::error::Error;
+#[cfg(feature = "schedule")]
+use std::io::Read;
+#[cfg(feature = "schedule")]
+use std::marker::PhantomData;
+
+#[cfg(feature = "schedule")]
+use substitution_common::{SubstitutionPDFExtractor, SubstitutionSchedule};
+
+#[cfg(feature = "schedule")]
+use crate::schedule_from_raw_tables;
+use crate::{HbsTableExtractor, RawPage};
+
+/// finds the tables in a loaded document, implement it to support layouts the default detection can't handle
+pub trait TableDetectionStrategy {
+	fn extract_tables(&self, extractor: &HbsTableExtractor) -> Result<Vec<RawPage>, Box<dyn Error>>;
+}
+
+/// the default detection, tables span from a "Block" header down to the time of the last block.
+/// like `HbsTableExtractor::schedule` it fails with `ExtractionError::UnpairedMarker` if a table has no end
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MarkerStrategy;
+
+impl TableDetectionStrategy for MarkerStrategy {
+	fn extract_tables(&self, extractor: &HbsTableExtractor) -> Result<Vec<RawPage>, Box<dyn Error>> {
+		if let Some(unpaired) = extractor.unpaired_tables().next() {
+			return Err(unpaired.into());
+		}
+
+		extractor.raw_tables()
+	}
+}
+
+/// makes any strategy usable wherever a `SubstitutionPDFExtractor` is expected
+#[cfg(feature = "schedule")]
+pub struct StrategyExtractor<S>(PhantomData<S>);
+
+#[cfg(feature = "schedule")]
+impl<S: TableDetectionStrategy + Default> SubstitutionPDFExtractor for StrategyExtractor<S> {
+	fn schedule_from_pdf<R: Read>(pdf: R) -> Result<SubstitutionSchedule, Box<dyn Error>> {
+		let extractor = HbsTableExtractor::load_from(pdf)?;
+		// checked first like in `schedule`, even though the default config has no filter to reject a day
+		let date = extractor.filtered_date()?;
+		let tables = S::default().extract_tables(&extractor)?;
+
+		schedule_from_raw_tables(&tables, date.timestamp_millis(), &extractor.config)
+	}
+}
+
+#[cfg(all(test, feature = "schedule"))]
+mod tests {
+	use substitution_common::SubstitutionPDFExtractor;
+
+	use crate::{ExtractionError, HbsTableExtractor, SyntheticPlan};
+
+	#[test]
+	fn table_without_bottom_marker_fails_the_trait_method() {
+		let mut document = SyntheticPlan::default().to_document();
+
+		// the time of the last block is the bottom marker
+		let page = document.page_iter().next().unwrap();
+		let mut content = document.get_and_decode_page_content(page).unwrap();
+		content.operations.retain(|op| op.operands.first().and_then(|o| o.as_str().ok()) != Some(b"14:30 - 15:15".as_slice()));
+		document.change_page_content(page, content.encode().unwrap()).unwrap();
+
+		let mut source = Vec::new();
+		document.save_to(&mut source).unwrap();
+
+		let error = HbsTableExtractor::schedule_from_pdf(source.as_slice()).unwrap_err();
+		assert!(matches!(error.downcast_ref(), Some(ExtractionError::UnpairedMarker { page: 0, .. })), "{error}");
+	}
+}
@@ -0,0 +1,236 @@
+#[cfg(feature = "schedule")]
+use std::error::Error;
+
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Document, Object, Stream, StringFormat};
+#[cfg(feature = "schedule")]
+use substitution_common::{SubstitutionColumn, SubstitutionSchedule};
+
+#[cfg(feature = "schedule")]
+use crate::Date;
+use crate::{RawColumn, RawPage};
+
+/// rows of a table below the header, one per block
+const ROWS: usize = 6;
+const ROW_HEIGHT: i64 = 30;
+const LABEL_WIDTH: i64 = 80;
+const CLASS_WIDTH: i64 = 90;
+const FONT_SIZE: i64 = 9;
+/// left edge of the table and baseline of the headers
+const LEFT: i64 = 40;
+const HEADER_Y: i64 = 700;
+/// time of the last block, the default bottom marker
+const BLOCK_TIMES: [&str; ROWS] = ["07:30 - 08:15", "08:20 - 09:50", "10:10 - 11:40", "12:00 - 13:30", "13:45 - 14:30", "14:30 - 15:15"];
+
+/// a made up single page plan in the layout the extractor expects by default, to check an installation end to end
+/// without a real plan and its personal data
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct SyntheticPlan {
+	/// dd.mm.yyyy, printed after "Datum: Montag, "
+	pub date: String,
+	/// every class with the lines of its 6 block cells, left to right
+	pub classes: Vec<(String, [Vec<String>; ROWS])>,
+}
+
+impl Default for SyntheticPlan {
+	fn default() -> Self {
+		let cell = |lines: &[&str]| lines.iter().map(|l| (*l).to_owned()).collect::<Vec<String>>();
+
+		Self {
+			date: "12.09.2022".to_owned(),
+			classes: vec![
+				("5a".to_owned(), [cell(&["Ma", "Mü"]), cell(&[]), cell(&["Entfall"]), cell(&[]), cell(&[]), cell(&[])]),
+				("6b".to_owned(), [cell(&[]), cell(&["De", "R 104"]), cell(&[]), cell(&[]), cell(&["Sp", "Halle 2"]), cell(&[])]),
+				("10c".to_owned(), [cell(&[]), cell(&[]), cell(&[]), cell(&["En"]), cell(&[]), cell(&["Entfall"])]),
+			],
+		}
+	}
+}
+
+impl SyntheticPlan {
+	pub fn to_document(&self) -> Document {
+		let right = LEFT + LABEL_WIDTH + CLASS_WIDTH * self.classes.len() as i64;
+		let bottom = HEADER_Y - 10 - ROW_HEIGHT * ROWS as i64;
+
+		let mut operations = Vec::new();
+
+		text(&mut operations, LEFT, 780, &format!("Datum: Montag, {}", self.date));
+		text(&mut operations, LEFT + 5, HEADER_Y, "Block");
+
+		for (idx, (class, _)) in self.classes.iter().enumerate() {
+			text(&mut operations, LEFT + LABEL_WIDTH + CLASS_WIDTH * idx as i64 + 5, HEADER_Y, class);
+		}
+
+		// the rule under the header and one under every row
+		for row in 0..=ROWS as i64 {
+			line(&mut operations, (LEFT, HEADER_Y - 10 - ROW_HEIGHT * row), (right, HEADER_Y - 10 - ROW_HEIGHT * row));
+		}
+
+		// up to the header baseline, the table starts there
+		let borders = std::iter::once(LEFT).chain((0..=self.classes.len() as i64).map(|idx| LEFT + LABEL_WIDTH + CLASS_WIDTH * idx));
+
+		for x in borders {
+			line(&mut operations, (x, bottom), (x, HEADER_Y));
+		}
+
+		for (row, time) in BLOCK_TIMES.iter().enumerate() {
+			let top = HEADER_Y - 10 - ROW_HEIGHT * row as i64;
+
+			text(&mut operations, LEFT + 5, top - 12, &(row + 1).to_string());
+			text(&mut operations, LEFT + 5, top - 24, time);
+
+			for (idx, (_, cells)) in self.classes.iter().enumerate() {
+				for (line_idx, content) in cells[row].iter().enumerate() {
+					text(&mut operations, LEFT + LABEL_WIDTH + CLASS_WIDTH * idx as i64 + 5, top - 12 - 12 * line_idx as i64, content);
+				}
+			}
+		}
+
+		let mut document = Document::with_version("1.5");
+
+		let font = document.add_object(dictionary! {
+			"Type" => "Font",
+			"Subtype" => "Type1",
+			"BaseFont" => "Helvetica",
+			"Encoding" => "WinAnsiEncoding",
+		});
+
+		let content = Content { operations }.encode().expect("content of plain operations encodes");
+		let contents = document.add_object(Stream::new(dictionary! {}, content));
+		let pages = document.new_object_id();
+
+		let page = document.add_object(dictionary! {
+			"Type" => "Page",
+			"Parent" => pages,
+			"Contents" => contents,
+			"Resources" => dictionary! { "Font" => dictionary! { "F1" => font } },
+		});
+
+		document.objects.insert(pages, Object::Dictionary(dictionary! {
+			"Type" => "Pages",
+			"Kids" => vec![page.into()],
+			"Count" => 1,
+			"MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+		}));
+
+		let catalog = document.add_object(dictionary! {
+			"Type" => "Catalog",
+			"Pages" => pages,
+		});
+
+		document.trailer.set("Root", catalog);
+		document
+	}
+
+	/// what `extract_tables` finds in `to_document`
+	pub fn expected_tables(&self) -> Vec<RawPage> {
+		let columns = self.classes.iter()
+			.map(|(class, cells)| std::iter::once(vec![class.clone()]).chain(cells.iter().cloned()).collect::<RawColumn>())
+			.collect();
+
+		vec![vec![columns]]
+	}
+
+	/// what `schedule` makes of `to_document`, every class with its 6 blocks, issued on `date`
+	#[cfg(feature = "schedule")]
+	pub fn expected_schedule(&self) -> Result<SubstitutionSchedule, Box<dyn Error>> {
+		let entries = self.classes.iter()
+			.map(|(class, cells)| Ok((class.clone(), SubstitutionColumn::from_2d_vec(cells.to_vec())?)))
+			.collect::<Result<_, Box<dyn Error>>>()?;
+
+		Ok(SubstitutionSchedule {
+			pdf_issue_date: Date::parse_dmy(&self.date)?.timestamp_millis(),
+			entries,
+		})
+	}
+}
+
+fn text(operations: &mut Vec<Operation>, x: i64, y: i64, text: &str) {
+	// WinAnsi, which matches latin-1 for the umlauts
+	let bytes = text.chars().map(|c| u8::try_from(u32::from(c)).unwrap_or(b'?')).collect::<Vec<u8>>();
+
+	operations.push(Operation::new("BT", vec![]));
+	operations.push(Operation::new("Tf", vec!["F1".into(), FONT_SIZE.into()]));
+	operations.push(Operation::new("Td", vec![x.into(), y.into()]));
+	operations.push(Operation::new("Tj", vec![Object::String(bytes, StringFormat::Literal)]));
+	operations.push(Operation::new("ET", vec![]));
+}
+
+fn line(operations: &mut Vec<Operation>, from: (i64, i64), to: (i64, i64)) {
+	operations.push(Operation::new("m", vec![from.0.into(), from.1.into()]));
+	operations.push(Operation::new("l", vec![to.0.into(), to.1.into()]));
+	operations.push(Operation::new("S", vec![]));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::HbsTableExtractor;
+
+	/// xorshift, enough to vary the plans without a dependency
+	struct Random(u64);
+
+	impl Random {
+		fn below(&mut self, n: usize) -> usize {
+			self.0 ^= self.0 << 13;
+			self.0 ^= self.0 >> 7;
+			self.0 ^= self.0 << 17;
+			(self.0 % n as u64) as usize
+		}
+
+		fn word(&mut self) -> String {
+			const LETTERS: &[char] = &['a', 'e', 'n', 'r', 'M', 'D', 'ä', 'ö', 'ü', 'ß', 'Ä', 'Ö', 'Ü', '1', '0'];
+
+			// up to far wider than a cell
+			let longest = if self.below(4) == 0 { 40 } else { 8 };
+			let len = 1 + self.below(longest);
+			(0..len).map(|_| LETTERS[self.below(LETTERS.len())]).collect()
+		}
+
+		fn plan(&mut self) -> SyntheticPlan {
+			let mut classes = Vec::new();
+
+			for idx in 0..1 + self.below(5) {
+				let cells = std::array::from_fn(|_| {
+					let lines = self.below(3);
+					(0..lines).map(|_| self.word()).collect()
+				});
+
+				classes.push((format!("{}{}", 5 + idx, ['a', 'b', 'c'][self.below(3)]), cells));
+			}
+
+			SyntheticPlan {
+				date: format!("{:02}.{:02}.{}", 1 + self.below(28), 1 + self.below(12), 2015 + self.below(10)),
+				classes,
+			}
+		}
+	}
+
+	#[test]
+	fn saved_default_plan_round_trips() {
+		let plan = SyntheticPlan::default();
+		let mut source = Vec::new();
+		plan.to_document().save_to(&mut source).unwrap();
+
+		let mut extractor = HbsTableExtractor::load_from(source.as_slice()).unwrap();
+
+		assert_eq!(extractor.extract_tables().unwrap(), plan.expected_tables());
+		#[cfg(feature = "schedule")]
+		assert_eq!(extractor.schedule().unwrap(), plan.expected_schedule().unwrap());
+	}
+
+	#[test]
+	fn random_plans_round_trip() {
+		let mut random = Random(0x9E37_79B9_7F4A_7C15);
+
+		for _ in 0..200 {
+			let plan = random.plan();
+			let mut extractor = HbsTableExtractor::from_document(plan.to_document()).unwrap();
+
+			assert_eq!(extractor.extract_tables().unwrap(), plan.expected_tables(), "{plan:?}");
+			#[cfg(feature = "schedule")]
+			assert_eq!(extractor.schedule().unwrap(), plan.expected_schedule().unwrap(), "{plan:?}");
+		}
+	}
+}
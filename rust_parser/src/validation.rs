@@ -0,0 +1,177 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::drift::Deviation;
+use crate::geometry::overlaps_1d;
+use crate::{HbsTableExtractor, TableObject};
+
+/// how far the layout may stray from an ideal table before `validate_geometry` complains
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[non_exhaustive]
+pub struct GeometryTolerance {
+	/// how far a line may lean before it is neither horizontal nor vertical
+	pub axis_alignment: i64,
+	/// how much the height of a row may differ from the median row of its column, in percent
+	pub row_spacing_percent: i64,
+}
+
+impl Default for GeometryTolerance {
+	fn default() -> Self {
+		Self {
+			axis_alignment: 1,
+			row_spacing_percent: 10,
+		}
+	}
+}
+
+/// everything `validate_geometry` found, empty if the layout is what the extractor expects
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GeometryReport {
+	pub violations: Vec<GeometryViolation>,
+}
+
+impl GeometryReport {
+	pub fn is_valid(&self) -> bool {
+		self.violations.is_empty()
+	}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum GeometryViolation {
+	SlantedLine { page: usize, table: usize, start: (i64, i64), end: (i64, i64) },
+	UnassembledColumn { page: usize, table: usize, column: usize, reason: String },
+	/// `column` starts left of where `previous` ends, columns counted left to right
+	OverlappingColumns { page: usize, table: usize, previous: usize, column: usize },
+	UnevenRow { page: usize, table: usize, column: usize, row: usize, height: i64, median: i64 },
+	/// a curve too curved to count as a line, it is left out of the table detection
+	IgnoredCurve { page: usize, start: (i64, i64), end: (i64, i64) },
+	/// a "Block" header or other top marker without a bottom marker below it, its table is left out
+	UnpairedTopMarker { page: usize, y: i64 },
+	/// a bottom marker without a table above it, like the time in the example of a legend
+	UnpairedBottomMarker { page: usize, y: i64 },
+	/// the document does not match `ExtractorConfig::template`, the school probably changed its template
+	TemplateChanged { deviations: Vec<Deviation> },
+}
+
+impl Display for GeometryViolation {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::SlantedLine { page, table, start, end } =>
+				write!(f, "page {page}, table {table}: line from {start:?} to {end:?} is neither horizontal nor vertical"),
+			Self::UnassembledColumn { page, table, column, reason } =>
+				write!(f, "page {page}, table {table}: column {column} could not be assembled: {reason}"),
+			Self::OverlappingColumns { page, table, previous, column } =>
+				write!(f, "page {page}, table {table}: column {column} overlaps column {previous}"),
+			Self::UnevenRow { page, table, column, row, height, median } =>
+				write!(f, "page {page}, table {table}, column {column}: row {row} is {height} high, the median is {median}"),
+			Self::IgnoredCurve { page, start, end } =>
+				write!(f, "page {page}: curve from {start:?} to {end:?} is not straight and was ignored"),
+			Self::UnpairedTopMarker { page, y } =>
+				write!(f, "page {page}: top marker at y {y} has no bottom marker below it"),
+			Self::UnpairedBottomMarker { page, y } =>
+				write!(f, "page {page}: bottom marker at y {y} has no top marker above it"),
+			Self::TemplateChanged { deviations } => {
+				let deviations = deviations.iter().map(Deviation::to_string).collect::<Vec<String>>();
+				write!(f, "the template changed since the profile was made: {}", deviations.join("; "))
+			}
+		}
+	}
+}
+
+impl HbsTableExtractor {
+	/// checks the invariants the table detection relies on, meant for debugging new layouts
+	pub fn validate_geometry(&self, tolerance: &GeometryTolerance) -> Result<GeometryReport, Box<dyn Error>> {
+		let mut violations = Vec::new();
+
+		for (page, objects) in self.pages.iter().enumerate() {
+			self.config.cancellation.check()?;
+
+			for curve in &objects.1 {
+				violations.push(GeometryViolation::IgnoredCurve {
+					page,
+					start: (curve.start.x, self.config.origin.y(curve.start.y, self.page_heights[page])),
+					end: (curve.end.x, self.config.origin.y(curve.end.y, self.page_heights[page])),
+				});
+			}
+
+			let pairs = objects.marker_pairs(&self.config);
+
+			for y in pairs.unpaired_tops {
+				violations.push(GeometryViolation::UnpairedTopMarker { page, y: self.config.origin.y(y, self.page_heights[page]) });
+			}
+
+			for y in pairs.unpaired_bottoms {
+				violations.push(GeometryViolation::UnpairedBottomMarker { page, y: self.config.origin.y(y, self.page_heights[page]) });
+			}
+
+			for (table, objects) in objects.extract_table_objects(&self.config)?.iter().enumerate() {
+				for object in &objects.0 {
+					if let TableObject::Line(l) = object {
+						if l.dx().abs() > tolerance.axis_alignment && l.dy().abs() > tolerance.axis_alignment {
+							violations.push(GeometryViolation::SlantedLine {
+								page,
+								table,
+								start: (l.start.x, self.config.origin.y(l.start.y, self.page_heights[page])),
+								end: (l.end.x, self.config.origin.y(l.end.y, self.page_heights[page])),
+							});
+						}
+					}
+				}
+
+				let mut extents = Vec::new();
+
+				for (column, mut assembling) in objects.extract_columns().into_iter().enumerate() {
+					let assembled = match assembling.assemble() {
+						Ok(assembled) => assembled,
+						Err(e) => {
+							violations.push(GeometryViolation::UnassembledColumn { page, table, column, reason: e.to_string() });
+							continue;
+						}
+					};
+
+					extents.push((column, assembled.left, assembled.right));
+
+					// the header row is allowed to differ, only the block rows have to line up
+					let heights = assembled.rulings[1..].windows(2)
+						.map(|w| w[0] - w[1])
+						.collect::<Vec<i64>>();
+
+					let mut sorted = heights.clone();
+					sorted.sort_unstable();
+
+					let median = match sorted.get(sorted.len() / 2) {
+						Some(median) => *median,
+						None => continue,
+					};
+
+					for (row, height) in heights.into_iter().enumerate() {
+						if (height - median).abs() * 100 > median * tolerance.row_spacing_percent {
+							violations.push(GeometryViolation::UnevenRow { page, table, column, row, height, median });
+						}
+					}
+				}
+
+				extents.sort_by_key(|(_, left, _)| *left);
+
+				for pair in extents.windows(2) {
+					let (previous, previous_left, previous_right) = pair[0];
+					let (column, left, right) = pair[1];
+
+					if overlaps_1d((previous_left, previous_right), (left, right), 0) {
+						violations.push(GeometryViolation::OverlappingColumns { page, table, previous, column });
+					}
+				}
+			}
+		}
+
+		Ok(GeometryReport { violations })
+	}
+}
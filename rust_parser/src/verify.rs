@@ -0,0 +1,38 @@
+use std::error::Error;
+use std::io::Read;
+
+use substitution_common::SubstitutionPDFExtractor;
+use tabula_pdf_parser::TabulaParser;
+
+use crate::diff::{diff_schedules, CellDifference};
+use crate::HbsTableExtractor;
+
+/// how the native extractor and tabula disagree on a pdf
+#[derive(Clone, Debug)]
+pub struct VerifyReport {
+	pub native_date: i64,
+	pub tabula_date: i64,
+	/// `old` is what the native extractor found, `new` what tabula found
+	pub differences: Vec<CellDifference>,
+}
+
+impl VerifyReport {
+	pub fn is_consistent(&self) -> bool {
+		self.native_date == self.tabula_date && self.differences.is_empty()
+	}
+}
+
+/// runs both backends on the pdf and compares their schedules cell by cell
+pub fn verify<R: Read>(mut pdf: R) -> Result<VerifyReport, Box<dyn Error>> {
+	let mut bytes = Vec::new();
+	pdf.read_to_end(&mut bytes)?;
+
+	let native = HbsTableExtractor::schedule_from_pdf(bytes.as_slice())?;
+	let tabula = TabulaParser::schedule_from_pdf(bytes.as_slice())?;
+
+	Ok(VerifyReport {
+		native_date: native.pdf_issue_date,
+		tabula_date: tabula.pdf_issue_date,
+		differences: diff_schedules(&native, &tabula),
+	})
+}
@@ -0,0 +1,64 @@
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use regex::Regex;
+use substitution_common::SubstitutionSchedule;
+
+use crate::date::Date;
+use crate::{add_column, schedule_from_raw_tables, HbsTableExtractor};
+
+/// a weekly overview, one schedule per day that has a table
+#[derive(Clone, Debug, Default)]
+pub struct WeekSchedule {
+	pub days: BTreeMap<Date, SubstitutionSchedule>,
+}
+
+impl HbsTableExtractor {
+	/// reads a weekly overview, where every table has a caption like "Montag, 12.09.2022" above it
+	/// instead of a single "Datum: " for the whole document. days the date filter rejects are left out
+	pub fn extract_week(&mut self) -> Result<WeekSchedule, Box<dyn Error>> {
+		let date_pattern = Regex::new(r"\b\d{1,2}\.\d{1,2}\.\d{4}\b").expect("valid regex");
+		let raw_pages = self.raw_tables()?;
+
+		let mut week = WeekSchedule::default();
+
+		for (page_idx, (page, raw_tables)) in self.pages.iter().zip(raw_pages).enumerate() {
+			let tables = page.extract_table_objects(&self.config)?;
+
+			for (table, raw_table) in tables.iter().zip(raw_tables) {
+				let top = table.bounding_box().map_or(i64::MIN, |b| b.top);
+
+				// the closest text above the table with a date in it
+				let caption = page.texts()
+					.filter(|t| t.position.y() > top)
+					.filter_map(|t| date_pattern.find(&t.text).map(|m| (t.position.y(), m.as_str())))
+					.min_by_key(|(y, _)| *y)
+					.map(|(_, date)| date)
+					.ok_or_else(|| format!("page {}: table without a dated caption above it", page_idx))?;
+
+				let date = Date::parse_dmy(caption)?;
+
+				if self.config.date_filter.as_ref().is_some_and(|f| !f.keeps(date)) {
+					continue;
+				}
+
+				let schedule = schedule_from_raw_tables(&[vec![raw_table]], date.timestamp_millis(), &self.config)?;
+
+				// a day can span several tables
+				match week.days.entry(date) {
+					Entry::Occupied(mut day) => {
+						for (class, column) in schedule.entries {
+							add_column(&mut day.get_mut().entries, class, column, &self.config)?;
+						}
+					}
+					Entry::Vacant(day) => {
+						day.insert(schedule);
+					}
+				}
+			}
+		}
+
+		Ok(week)
+	}
+}
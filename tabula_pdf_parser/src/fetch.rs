@@ -0,0 +1,53 @@
+use std::error::Error;
+use std::fs::{create_dir_all, read, rename, write};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use tracing::debug;
+
+use crate::{TabulaConfig, TabulaError};
+
+/// Upper bound for the size of a download, the jar with dependencies has about 12 MB.
+const MAX_JAR_SIZE: u64 = 64 * 1024 * 1024;
+
+impl TabulaConfig {
+	/// Makes sure `dir/tabula.jar` is the tabula release `version`, like "1.0.5", and returns its path.
+	///
+	/// A jar that is already there and matches `jar_sha256` (or `SUPPORTED_TABULA_JARS`) is kept, otherwise the release is downloaded from
+	/// GitHub and only written once its digest matches. Point `jar` at the result or use
+	/// `$XDG_DATA_HOME/tabula` as `dir` so `locate_jar` finds it.
+	///
+	/// # Errors
+	///
+	/// Returns `TabulaError::NoChecksum` if there is no digest to check against, `TabulaError::TabulaVersionUnsupported` if the
+	/// download doesn't match, or the error of the download or of writing the jar.
+	pub fn ensure_tabula(&self, version: &str, dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+		if self.accepted_digests().is_empty() {
+			return Err(TabulaError::NoChecksum.into());
+		}
+
+		let jar = dir.join("tabula.jar");
+
+		if let Ok(bytes) = read(&jar) {
+			if self.check_digest(&jar, &bytes).is_ok() {
+				return Ok(jar);
+			}
+		}
+
+		let url = format!("https://github.com/tabulapdf/tabula-java/releases/download/v{version}/tabula-{version}-jar-with-dependencies.jar");
+
+		debug!("Downloading {url}");
+		let mut bytes = Vec::new();
+		ureq::get(&url).call()?.into_reader().take(MAX_JAR_SIZE).read_to_end(&mut bytes)?;
+
+		self.check_digest(Path::new(&url), &bytes)?;
+
+		// written next to the jar and renamed, so a crash never leaves a truncated jar behind
+		create_dir_all(dir)?;
+		let partial = dir.join("tabula.jar.part");
+		write(&partial, &bytes)?;
+		rename(&partial, &jar)?;
+
+		Ok(jar)
+	}
+}
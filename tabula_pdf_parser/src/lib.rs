@@ -1,24 +1,282 @@
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
-use std::fs::File;
-use std::io::{Read, Write};
-use std::process::Command;
+use std::env::{current_exe, var_os};
+use std::fs::{read, remove_dir_all, DirBuilder, OpenOptions};
+use std::io::{self, Read, Write};
+#[cfg(unix)]
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::{absolute, Path, PathBuf};
+use std::process::{Command, ExitStatus};
 use std::str;
 
-use chrono::{Local, NaiveDate, Offset, Utc};
+use hbs_date::Date;
 use lopdf::Document;
+use regex::Regex;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value;
-use substitution_common::{PDFJsonError, SubstitutionColumn, SubstitutionPDFExtractor, SubstitutionSchedule, Substitution};
+use sha2::{Digest, Sha256};
+use substitution_common::{PDFJsonError, SubstitutionColumn, SubstitutionPDFExtractor, SubstitutionSchedule};
 use substitution_common::util::{get_random_name, make_temp_dir};
-use tracing::debug;
+use thiserror::Error;
+use tracing::{debug, warn};
+
+#[cfg(feature = "tabula-fetch")]
+mod fetch;
+
+/// Number of blocks in a day, `SubstitutionColumn` has a field for each.
+const BLOCKS: usize = 6;
+
+/// Tolerance in PDF units when comparing the geometry of tabula cells.
+const CELL_EPSILON: f64 = 0.5;
+
+/// Versions and lowercase hex SHA-256 digests of the tabula-java release jars (`jar-with-dependencies`) the parser
+/// supports, accepted when `TabulaConfig::jar_sha256` is empty. Add a release here once its output is checked.
+pub const SUPPORTED_TABULA_JARS: &[(&str, &str)] = &[];
+
+/// Extracts substitution schedules by handing the PDF to tabula.
+pub struct TabulaParser;
+
+/// Settings of the `TabulaParser`.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct TabulaConfig {
+	pub row_labels: RowLabels,
+	/// Restrictions for the java process running tabula.
+	pub sandbox: Sandbox,
+	/// The tabula.jar to run, searched for if not set, see `TabulaConfig::locate_jar`.
+	pub jar: Option<PathBuf>,
+	/// Lowercase hex SHA-256 digests of the tabula.jar builds that are accepted, the ones of
+	/// `SUPPORTED_TABULA_JARS` if empty.
+	pub jar_sha256: Vec<String>,
+}
+
+/// Errors around finding and checking tabula.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum TabulaError {
+	#[error("tabula.jar not found, searched {}", display_paths(.searched))]
+	TabulaNotFound { searched: Vec<PathBuf> },
+	#[error("{} has the SHA-256 {sha256}, which is not one of the accepted tabula versions", .path.display())]
+	TabulaVersionUnsupported { path: PathBuf, sha256: String },
+	#[error("refusing to download tabula without a SHA-256 in TabulaConfig::jar_sha256 to check it against")]
+	NoChecksum,
+	#[error("tabula failed with {status}: {stderr}")]
+	TabulaFailed { status: ExitStatus, stderr: String },
+}
+
+fn display_paths(paths: &[PathBuf]) -> String {
+	paths.iter().map(|p| p.display().to_string()).collect::<Vec<String>>().join(", ")
+}
+
+impl TabulaConfig {
+	/// Finds tabula.jar and checks it against `jar_sha256`.
+	///
+	/// The first existing file of `jar`, the `TABULA_JAR` environment variable, `$XDG_DATA_HOME/tabula/tabula.jar`
+	/// (`~/.local/share` if unset), `tabula.jar` or `tabula/tabula.jar` next to the executable and
+	/// `./tabula/tabula.jar` is used. A set `jar` is never replaced by one of the others.
+	///
+	/// # Errors
+	///
+	/// Returns `TabulaError::TabulaNotFound` if none of them exists, `TabulaError::TabulaVersionUnsupported` if the
+	/// digest doesn't match, or the error of reading the jar.
+	pub fn locate_jar(&self) -> Result<PathBuf, Box<dyn Error>> {
+		let searched = match &self.jar {
+			Some(jar) => vec![jar.clone()],
+			None => jar_candidates(),
+		};
+
+		let jar = match searched.iter().find(|p| p.is_file()) {
+			Some(jar) => absolute(jar)?,
+			None => return Err(TabulaError::TabulaNotFound { searched }.into()),
+		};
+
+		if self.accepted_digests().is_empty() {
+			warn!("{} is not checked, no SHA-256 of a supported tabula version is known", jar.display());
+		} else {
+			self.check_digest(&jar, &read(&jar)?)?;
+		}
+
+		Ok(jar)
+	}
+
+	/// Fails with `TabulaError::TabulaVersionUnsupported` if `bytes`, the content of `path`, isn't one of the
+	/// accepted jars.
+	fn check_digest(&self, path: &Path, bytes: &[u8]) -> Result<(), TabulaError> {
+		let sha256 = format!("{:x}", Sha256::digest(bytes));
+
+		if self.accepted_digests().iter().any(|accepted| accepted.eq_ignore_ascii_case(&sha256)) {
+			Ok(())
+		} else {
+			Err(TabulaError::TabulaVersionUnsupported { path: path.to_owned(), sha256 })
+		}
+	}
+
+	/// `jar_sha256`, or the digests of `SUPPORTED_TABULA_JARS` if it is empty.
+	fn accepted_digests(&self) -> Vec<&str> {
+		if self.jar_sha256.is_empty() {
+			SUPPORTED_TABULA_JARS.iter().map(|(_, sha256)| *sha256).collect()
+		} else {
+			self.jar_sha256.iter().map(String::as_str).collect()
+		}
+	}
+}
+
+/// Where tabula.jar is looked for when `TabulaConfig::jar` isn't set, in order.
+fn jar_candidates() -> Vec<PathBuf> {
+	let mut candidates = Vec::new();
+
+	if let Some(jar) = var_os("TABULA_JAR") {
+		candidates.push(PathBuf::from(jar));
+	}
+
+	let data_home = var_os("XDG_DATA_HOME")
+		.map(PathBuf::from)
+		.or_else(|| var_os("HOME").map(|home| Path::new(&home).join(".local/share")));
+
+	if let Some(data_home) = data_home {
+		candidates.push(data_home.join("tabula/tabula.jar"));
+	}
+
+	if let Some(dir) = current_exe().ok().as_deref().and_then(Path::parent) {
+		candidates.push(dir.join("tabula.jar"));
+		candidates.push(dir.join("tabula/tabula.jar"));
+	}
+
+	candidates.push(PathBuf::from("./tabula/tabula.jar"));
+
+	candidates
+}
+
+/// Contains the java process running tabula on untrusted PDFs, nothing is restricted by default.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct Sandbox {
+	/// Start java with an empty environment, apart from the variables in `keep_env`.
+	pub clear_env: bool,
+	/// Variables passed on when `clear_env` is set, only `PATH` by default so java can still be found.
+	pub keep_env: Vec<String>,
+	/// The working directory of the process, the PDF and jar are passed as absolute paths so they don't have to
+	/// be inside it.
+	pub working_dir: Option<PathBuf>,
+	/// A command `java` is run through, like `["systemd-run", "--user", "--pipe", "--wait", "--quiet"]`
+	/// or `["bwrap", "--ro-bind", "/", "/", "--"]`.
+	pub wrapper: Vec<String>,
+}
+
+impl Default for Sandbox {
+	fn default() -> Self {
+		Self {
+			clear_env: false,
+			keep_env: vec!["PATH".to_owned()],
+			working_dir: None,
+			wrapper: Vec::new(),
+		}
+	}
+}
+
+impl Sandbox {
+	/// The command running `program` under these restrictions.
+	fn command(&self, program: &str) -> Command {
+		let mut command = match self.wrapper.split_first() {
+			Some((wrapper, args)) => {
+				let mut command = Command::new(wrapper);
+				command.args(args).arg(program);
+				command
+			}
+			None => Command::new(program),
+		};
+
+		if self.clear_env {
+			command.env_clear();
+			command.envs(self.keep_env.iter().filter_map(|key| Some((key, std::env::var_os(key)?))));
+		}
+
+		if let Some(dir) = &self.working_dir {
+			command.current_dir(dir);
+		}
+
+		command
+	}
+}
+
+/// Recognizes which rows of a table belong to which block by the label in their first cell.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct RowLabels {
+	/// A row whose label matches one of these starts a new block.
+	pub block_start: Vec<Regex>,
+	/// A row whose label matches one of these is the last row of its block.
+	pub block_end: Vec<Regex>,
+	/// Blocks after this many are ignored.
+	pub max_blocks: usize,
+}
+
+impl Default for RowLabels {
+	fn default() -> Self {
+		Self {
+			block_start: vec![
+				Regex::new(r"^Block ?\d").expect("invalid builtin regex"),
+				Regex::new(r"^\d+\.?$").expect("invalid builtin regex"),
+			],
+			block_end: vec![
+				Regex::new(r"^-").expect("invalid builtin regex"),
+				Regex::new(r"\d{1,2}:\d{2}\s*-\s*\d{1,2}:\d{2}").expect("invalid builtin regex"),
+			],
+			max_blocks: 5,
+		}
+	}
+}
+
+impl RowLabels {
+	/// Splits the rows below the header into blocks.
+	///
+	/// A block ends at a row with an end label, before a row with a start label, or, when tabula merged the
+	/// label cells of a block into one, at the last row inside that merged cell. The table's last row always
+	/// ends the last block, so the rows can't be overrun.
+	fn split_blocks<'a>(&self, rows: &'a [Row]) -> Vec<&'a [Row]> {
+		let mut blocks = Vec::new();
+		let mut start = 0;
+		let mut label_bottom = None;
+
+		for (idx, row) in rows.iter().enumerate() {
+			if let Some(bottom) = row.merged_label_bottom() {
+				label_bottom = Some(bottom);
+			}
 
-struct TabulaParser;
+			let next = rows.get(idx + 1);
+
+			let ends = next.is_none()
+				|| self.block_end.iter().any(|r| r.is_match(row.label()))
+				|| next.is_some_and(|n| self.block_start.iter().any(|r| r.is_match(n.label())))
+				|| next.zip(label_bottom).is_some_and(|(n, bottom)| n.top() >= bottom - CELL_EPSILON);
+
+			if ends {
+				blocks.push(&rows[start..=idx]);
+				start = idx + 1;
+				label_bottom = None;
+			}
+		}
+
+		blocks
+	}
+}
 
 impl SubstitutionPDFExtractor for TabulaParser {
 	fn schedule_from_pdf<R: Read>(pdf: R) -> Result<SubstitutionSchedule, Box<dyn Error>> {
+		Self::schedule_from_pdf_with_config(pdf, &TabulaConfig::default())
+	}
+}
+
+impl TabulaParser {
+	/// Like `schedule_from_pdf` but with custom settings.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the PDF can't be read, tabula fails (`TabulaError::TabulaFailed` with its stderr) or the
+	/// tables don't have the expected layout.
+	pub fn schedule_from_pdf_with_config<R: Read>(pdf: R, config: &TabulaConfig) -> Result<SubstitutionSchedule, Box<dyn Error>> {
 		let bytes = pdf.bytes().collect::<Result<Box<[u8]>, std::io::Error>>()?;
 
 		let pdf = match Document::load_mem(&bytes) {
@@ -32,111 +290,157 @@ impl SubstitutionPDFExtractor for TabulaParser {
 		let date_idx_start = pdf.find("Datum: ").ok_or("date not found")?;
 		let date_idx_end = pdf[date_idx_start..].find('\n').ok_or("date end not found")? + date_idx_start;
 
-		let date_str: Vec<u32> = pdf[date_idx_start..date_idx_end].split(", ")
-			.last()
-			.ok_or("date string has no ','")?
-			.split('.')
-			.collect::<Vec<&str>>()
-			.iter()
-			.map(|s| (*s).parse::<u32>().unwrap())
-			.collect();
-
-		#[allow(clippy::cast_possible_wrap)]
-			let date = chrono::Date::<Local>::from_utc(
-			NaiveDate::from_ymd(date_str[2] as i32, date_str[1], date_str[0]),
-			Utc.fix(),
-		).and_hms_milli(0, 0, 0, 0).timestamp_millis();
-
-
-		let temp_dir = make_temp_dir();
-		let random_name = get_random_name();
-		let path = format!("{temp_dir}/{random_name}");
-		let mut file = File::open(path.as_str())?;
-		file.write_all(&bytes)?;
-
-		debug!("Calling tabula");
-		let output = Command::new("java")
-			.arg("-jar")
-			.arg("./tabula/tabula.jar")
-			.arg("-g")
-			.arg("-f")
-			.arg("JSON")
-			.arg("-p")
-			.arg("all")
-			.arg(path)
-			.output()?;
+		let date = parse_date(&pdf[date_idx_start..date_idx_end])?;
+
+		let output = run_tabula(&bytes, config)?;
 
 		debug!("Parsing tabulas json");
-		let table = parse_tabula_json(str::from_utf8(&output.stdout).unwrap())?;
+		let tables = parse_tabula_rows(&output)?;
 
-		Ok(Self::schedule_from_tables(&table, date))
+		Self::schedule_from_tables(&tables, date, &config.row_labels)
 	}
-}
 
-impl TabulaParser {
-	/// Constructs an instance of `SubstitutionSchedule` from a table.
-	#[allow(clippy::ptr_arg)]
-	pub fn schedule_from_tables(tables: &Vec<Vec<Vec<String>>>, pdf_create_date: i64) -> SubstitutionSchedule {
+	/// Runs tabula on the PDF and returns the tables with the page and position of every cell.
+	///
+	/// # Errors
+	///
+	/// Returns an error if tabula fails (`TabulaError::TabulaFailed` with its stderr) or its output can't be parsed.
+	pub fn tables_with_geometry<R: Read>(pdf: R) -> Result<Vec<TabulaTable>, Box<dyn Error>> {
+		Self::tables_with_geometry_with_config(pdf, &TabulaConfig::default())
+	}
+
+	/// Like `tables_with_geometry` but with custom settings, the row labels aren't used.
+	///
+	/// # Errors
+	///
+	/// Returns an error if tabula fails (`TabulaError::TabulaFailed` with its stderr) or its output can't be parsed.
+	pub fn tables_with_geometry_with_config<R: Read>(mut pdf: R, config: &TabulaConfig) -> Result<Vec<TabulaTable>, Box<dyn Error>> {
+		let mut bytes = Vec::new();
+		pdf.read_to_end(&mut bytes)?;
+
+		parse_tabula_geometry(&run_tabula(&bytes, config)?)
+	}
+
+	/// Constructs an instance of `SubstitutionSchedule` from the tables.
+	fn schedule_from_tables(tables: &[Vec<Row>], pdf_create_date: i64, labels: &RowLabels) -> Result<SubstitutionSchedule, Box<dyn Error>> {
 		let mut entries = HashMap::new();
 
 		for table in tables {
-			entries.extend(Self::table_to_substitutions(table));
+			entries.extend(Self::table_to_substitutions(table, labels)?);
 		}
 
-		SubstitutionSchedule {
+		Ok(SubstitutionSchedule {
 			pdf_issue_date: pdf_create_date,
 			entries,
-		}
+		})
 	}
 
 	/// Grabs the classes and their substitutions from a table and turns them into a `HashMap`.
-	#[allow(clippy::ptr_arg)]
-	fn table_to_substitutions(table: &Vec<Vec<String>>) -> HashMap<String, SubstitutionColumn> {
-		let mut entries: HashMap<String, SubstitutionColumn> = HashMap::new();
-
-		let classes = &table[0][1..];
-
-		for class in classes {
-			entries.insert(class.to_string(), SubstitutionColumn::new());
-		}
+	///
+	/// Blocks 1 to 6 are handed to `SubstitutionColumn::from_2d_vec`, the same six blocks the native extractor maps
+	/// with its default `RowMapper`.
+	fn table_to_substitutions(table: &[Row], labels: &RowLabels) -> Result<HashMap<String, SubstitutionColumn>, Box<dyn Error>> {
+		let (header, rows) = table.split_first().ok_or("table has no rows")?;
+		let classes = header.row.iter().skip(1).map(|c| c.text.as_str()).collect::<Vec<&str>>();
+
+		let mut blocks = vec![vec![Vec::new(); BLOCKS]; classes.len()];
+
+		for (lesson_idx, block_rows) in labels.split_blocks(rows).into_iter().take(labels.max_blocks).enumerate() {
+			if lesson_idx >= BLOCKS {
+				return Err("more than 6 blocks configured".into());
+			}
 
-		let mut row = 1;
-
-		for lesson_idx in 0..5 {
-			loop {
-				for (i, substitution_part) in table[row][1..].iter().enumerate() {
-					let substitutions = entries.get_mut(&classes[i]).unwrap();
-
-					let block_option = match lesson_idx {
-						0 => &mut substitutions.block_0,
-						1 => &mut substitutions.block_1,
-						2 => &mut substitutions.block_2,
-						3 => &mut substitutions.block_3,
-						4 => &mut substitutions.block_4,
-						5 => &mut substitutions.block_5,
-						_ => panic!("more then 5 lessons used"),
-					};
-
-					if !substitution_part.is_empty() {
-						if let Some(block) = block_option {
-							block.0.push(substitution_part.clone())
-						} else {
-							let _ = block_option.insert(Substitution(vec![substitution_part.clone()]));
-						}
+			for row in block_rows {
+				for (class_blocks, substitution_part) in blocks.iter_mut().zip(row.row.iter().skip(1)) {
+					if !substitution_part.text.is_empty() {
+						class_blocks[lesson_idx].push(substitution_part.text.clone());
 					}
 				}
-
-				if table[row][0].starts_with('-') {
-					break;
-				}
-				row += 1;
 			}
+		}
+
+		classes.into_iter()
+			.zip(blocks)
+			.map(|(class, class_blocks)| Ok((class.to_string(), SubstitutionColumn::from_2d_vec(class_blocks)?)))
+			.collect()
+	}
+}
+
+/// Parses a date line like "Datum: Montag, 01.02.2021" into the milliseconds since the unix epoch at midnight UTC.
+fn parse_date(line: &str) -> Result<i64, Box<dyn Error>> {
+	let date = line.split(", ").last().ok_or("date string has no ','")?;
+
+	Ok(Date::parse_dmy(date)?.timestamp_millis())
+}
+
+/// The PDF handed to tabula, alone in a directory only the current user can access.
+///
+/// The PDF holds personal data, so the directory is removed when the guard is dropped, which also happens when
+/// tabula fails or the thread panics.
+struct TempPdf {
+	dir: PathBuf,
+	path: PathBuf,
+}
+
+impl TempPdf {
+	fn write(bytes: &[u8]) -> io::Result<Self> {
+		// absolute, the sandbox may run tabula in another working directory
+		let dir = absolute(Path::new(&make_temp_dir()).join(get_random_name()))?;
 
-			row += 1;
+		let mut builder = DirBuilder::new();
+		#[cfg(unix)]
+		builder.mode(0o700);
+		builder.create(&dir)?;
+
+		// from here on a failed write still removes the directory
+		let pdf = Self {
+			path: dir.join("schedule.pdf"),
+			dir,
+		};
+
+		let mut options = OpenOptions::new();
+		options.write(true).create_new(true);
+		#[cfg(unix)]
+		options.mode(0o600);
+		options.open(&pdf.path)?.write_all(bytes)?;
+
+		Ok(pdf)
+	}
+}
+
+impl Drop for TempPdf {
+	fn drop(&mut self) {
+		if let Err(e) = remove_dir_all(&self.dir) {
+			warn!("could not remove {}: {e}", self.dir.display());
 		}
+	}
+}
 
-		entries
+/// Writes the PDF to a temporary file and returns the JSON tabula outputs for it.
+fn run_tabula(bytes: &[u8], config: &TabulaConfig) -> Result<String, Box<dyn Error>> {
+	let jar = config.locate_jar()?;
+	let pdf = TempPdf::write(bytes)?;
+
+	debug!("Calling tabula");
+	let output = config.sandbox.command("java")
+		.arg("-jar")
+		.arg(jar)
+		.arg("-g")
+		.arg("-f")
+		.arg("JSON")
+		.arg("-p")
+		.arg("all")
+		.arg(&pdf.path)
+		.output()?;
+
+	if !output.status.success() {
+		return Err(TabulaError::TabulaFailed {
+			status: output.status,
+			stderr: String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+		}.into());
 	}
+
+	Ok(str::from_utf8(&output.stdout)?.to_owned())
 }
 
 /// Extracts the text from the rows and cells in the json that gets outputted by tabula.
@@ -145,6 +449,34 @@ impl TabulaParser {
 ///
 /// Returns an error
 pub fn parse_tabula_json(content: &str) -> Result<Vec<Vec<Vec<String>>>, Box<dyn std::error::Error>> {
+	let tables = parse_tabula_rows(content)?;
+
+	let mut tables_with_rows_as_text = Vec::new();
+	for table_rows in tables {
+		let mut rows_as_text = Vec::new();
+		for mut row in table_rows {
+			rows_as_text.push(row.extract_text());
+		}
+		tables_with_rows_as_text.push(rows_as_text);
+	}
+
+	Ok(tables_with_rows_as_text)
+}
+
+/// Parses the json that gets outputted by tabula into tables of rows.
+fn parse_tabula_rows(content: &str) -> Result<Vec<Vec<Row>>, Box<dyn std::error::Error>> {
+	Ok(parse_tabula_geometry(content)?
+		.into_iter()
+		.map(|table| table.rows.into_iter().map(|row| Row { row }).collect())
+		.collect())
+}
+
+/// Parses the json that gets outputted by tabula, keeping the page and the position of every cell.
+///
+/// # Errors
+///
+/// Returns an error if the json doesn't have the layout tabula produces.
+pub fn parse_tabula_geometry(content: &str) -> Result<Vec<TabulaTable>, Box<dyn std::error::Error>> {
 	let json: Value = serde_json::from_str(content)?;
 	let array = json.as_array().ok_or("Json malformed")?;
 
@@ -153,27 +485,31 @@ pub fn parse_tabula_json(content: &str) -> Result<Vec<Vec<Vec<String>>>, Box<dyn
 		let object = entry.as_object().ok_or("Json malformed")?;
 		let data = object.get("data").ok_or("Json data field missing")?;
 
-		let mut table_rows = Vec::new();
+		let page_number = object.get("page_number")
+			.and_then(Value::as_u64)
+			.map(u32::try_from)
+			.transpose()?;
+
+		let mut rows = Vec::new();
 		for row in data.as_array().ok_or("Json data missing")? {
-			let row: Vec<Cell> = serde_json::from_value(row.clone())?;
-			let row = Row {
-				row
-			};
-			table_rows.push(row);
+			rows.push(serde_json::from_value(row.clone())?);
 		}
-		tables.push(table_rows);
-	}
 
-	let mut tables_with_rows_as_text = Vec::new();
-	for table_rows in tables {
-		let mut rows_as_text = Vec::new();
-		for mut row in table_rows {
-			rows_as_text.push(row.extract_text());
-		}
-		tables_with_rows_as_text.push(rows_as_text);
+		tables.push(TabulaTable {
+			page_number,
+			rows,
+		});
 	}
 
-	Ok(tables_with_rows_as_text)
+	Ok(tables)
+}
+
+/// A table as tabula found it.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TabulaTable {
+	/// The page the table is on, counting from 1, if tabula reported it.
+	pub page_number: Option<u32>,
+	pub rows: Vec<Vec<Cell>>,
 }
 
 /// A row in the substitution table
@@ -192,16 +528,38 @@ impl Row {
 
 		text
 	}
+
+	/// The trimmed text of the first cell, which labels the row.
+	fn label(&self) -> &str {
+		self.row.first().map_or("", |c| c.text.trim())
+	}
+
+	/// The top of the highest cell of the row.
+	fn top(&self) -> f64 {
+		self.row.iter().map(|c| c.top).fold(f64::INFINITY, f64::min)
+	}
+
+	/// The bottom of the label cell if tabula merged it over several rows.
+	fn merged_label_bottom(&self) -> Option<f64> {
+		let (label, cells) = self.row.split_first()?;
+		let row_height = cells.iter().map(|c| c.height).fold(0.0, f64::max);
+
+		if !label.text.trim().is_empty() && label.height > row_height + CELL_EPSILON {
+			Some(label.top + label.height)
+		} else {
+			None
+		}
+	}
 }
 
-/// A cell in the substitution table
-#[derive(Debug, Deserialize, Serialize)]
-struct Cell {
-	top: f64,
-	left: f64,
-	width: f64,
-	height: f64,
-	text: String,
+/// A cell in the substitution table, positions are in PDF units from the top left corner of the page
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Cell {
+	pub top: f64,
+	pub left: f64,
+	pub width: f64,
+	pub height: f64,
+	pub text: String,
 }
 
 impl Display for Cell {
@@ -221,3 +579,22 @@ fn get_all_page_numbers(pdf: &Document) -> Box<[u32]> {
 	pages
 }
 
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn jar_with_another_digest_is_unsupported() {
+		let config = TabulaConfig {
+			jar_sha256: vec![format!("{:x}", Sha256::digest(b"tabula"))],
+			..TabulaConfig::default()
+		};
+
+		assert!(config.check_digest(Path::new("tabula.jar"), b"tabula").is_ok());
+		assert!(matches!(
+			config.check_digest(Path::new("tabula.jar"), b"other"),
+			Err(TabulaError::TabulaVersionUnsupported { .. })
+		));
+	}
+}